@@ -0,0 +1,279 @@
+// Pure-Rust AES, following FIPS-197 byte by byte.
+// The state is stored column by column, as in the specification.
+
+use crate::{
+    ciphers::block::BlockCipher,
+    error::{Error, Result},
+};
+
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const INV_SBOX: [u8; 256] = [
+    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
+    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
+    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
+    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
+    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
+    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
+    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
+    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
+    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
+    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
+    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
+    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
+    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
+    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
+    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d,
+];
+
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Multiplication by x in GF(2^8).
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// Multiplication in GF(2^8).
+fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut res = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            res ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    res
+}
+
+/// Expands a 16, 24 or 32-byte key into the round keys.
+fn expand_key(key: &[u8]) -> Vec<[u8; 16]> {
+    let nk = key.len() / 4;
+    let rounds = nk + 6;
+    let mut w: Vec<[u8; 4]> = key.chunks(4).map(|c| c.try_into().unwrap()).collect();
+    for i in nk..4 * (rounds + 1) {
+        let mut temp = w[i - 1];
+        if i % nk == 0 {
+            temp.rotate_left(1);
+            temp = temp.map(|b| SBOX[b as usize]);
+            temp[0] ^= RCON[i / nk - 1];
+        } else if nk > 6 && i % nk == 4 {
+            temp = temp.map(|b| SBOX[b as usize]);
+        }
+        let prev = w[i - nk];
+        w.push([0, 1, 2, 3].map(|j| prev[j] ^ temp[j]));
+    }
+    w.chunks(4)
+        .map(|words| {
+            let mut round_key = [0u8; 16];
+            for (j, word) in words.iter().enumerate() {
+                round_key[4 * j..4 * j + 4].copy_from_slice(word);
+            }
+            round_key
+        })
+        .collect()
+}
+
+fn add_round_key(state: &mut [u8], round_key: &[u8; 16]) {
+    for (s, k) in state.iter_mut().zip(round_key) {
+        *s ^= k;
+    }
+}
+
+fn sub_bytes(state: &mut [u8], sbox: &[u8; 256]) {
+    for s in state.iter_mut() {
+        *s = sbox[*s as usize];
+    }
+}
+
+/// Row r is rotated left by r positions.
+fn shift_rows(state: &mut [u8]) {
+    let old: [u8; 16] = state.try_into().unwrap();
+    for r in 1..4 {
+        for c in 0..4 {
+            state[r + 4 * c] = old[r + 4 * ((c + r) % 4)];
+        }
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8]) {
+    let old: [u8; 16] = state.try_into().unwrap();
+    for r in 1..4 {
+        for c in 0..4 {
+            state[r + 4 * ((c + r) % 4)] = old[r + 4 * c];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8]) {
+    for col in state.chunks_mut(4) {
+        let a: [u8; 4] = (&*col).try_into().unwrap();
+        let all = a[0] ^ a[1] ^ a[2] ^ a[3];
+        for r in 0..4 {
+            // 2 * a[r] + 3 * a[r + 1] + a[r + 2] + a[r + 3]
+            col[r] = a[r] ^ all ^ xtime(a[r] ^ a[(r + 1) % 4]);
+        }
+    }
+}
+
+fn inv_mix_columns(state: &mut [u8]) {
+    for col in state.chunks_mut(4) {
+        let a: [u8; 4] = (&*col).try_into().unwrap();
+        for r in 0..4 {
+            col[r] = gmul(a[r], 14)
+                ^ gmul(a[(r + 1) % 4], 11)
+                ^ gmul(a[(r + 2) % 4], 13)
+                ^ gmul(a[(r + 3) % 4], 9);
+        }
+    }
+}
+
+/// Expanded AES key, for any of the three key sizes.
+struct AesKey {
+    round_keys: Vec<[u8; 16]>,
+}
+
+impl AesKey {
+    fn new(key: &[u8]) -> Self {
+        Self {
+            round_keys: expand_key(key),
+        }
+    }
+
+    fn encrypt_block(&self, state: &mut [u8]) {
+        assert_eq!(state.len(), 16, "AES block must be 16 bytes");
+        let rounds = self.round_keys.len() - 1;
+        add_round_key(state, &self.round_keys[0]);
+        for round in 1..=rounds {
+            sub_bytes(state, &SBOX);
+            shift_rows(state);
+            if round != rounds {
+                mix_columns(state);
+            }
+            add_round_key(state, &self.round_keys[round]);
+        }
+    }
+
+    fn decrypt_block(&self, state: &mut [u8]) {
+        assert_eq!(state.len(), 16, "AES block must be 16 bytes");
+        let rounds = self.round_keys.len() - 1;
+        add_round_key(state, &self.round_keys[rounds]);
+        for round in (0..rounds).rev() {
+            inv_shift_rows(state);
+            sub_bytes(state, &INV_SBOX);
+            add_round_key(state, &self.round_keys[round]);
+            if round != 0 {
+                inv_mix_columns(state);
+            }
+        }
+    }
+}
+
+macro_rules! soft_aes {
+    ($name:ident, $key_len:expr) => {
+        #[doc = concat!("AES with a ", stringify!($key_len), "-byte key.")]
+        /// The key schedule is expanded once, and reused for every block.
+        pub struct $name {
+            key: AesKey,
+        }
+
+        impl $name {
+            pub fn new(key: &[u8]) -> Result<Self> {
+                if key.len() != $key_len {
+                    return Err(Error::BadKeyLength(key.len()));
+                }
+                Ok(Self {
+                    key: AesKey::new(key),
+                })
+            }
+        }
+
+        impl BlockCipher for $name {
+            fn block_size(&self) -> usize {
+                16
+            }
+
+            fn encrypt_block(&self, block: &mut [u8]) {
+                self.key.encrypt_block(block);
+            }
+
+            fn decrypt_block(&self, block: &mut [u8]) {
+                self.key.decrypt_block(block);
+            }
+        }
+    };
+}
+
+soft_aes!(Aes128, 16);
+soft_aes!(Aes192, 24);
+soft_aes!(Aes256, 32);
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        ciphers::modes::{decrypt_cbc, decrypt_ecb, encrypt_cbc},
+        convert::{from_base64, from_hex, to_hex},
+    };
+
+    use super::*;
+
+    fn check<C: BlockCipher>(cipher: &C, plain: &str, expected: &str) {
+        let mut block = from_hex(plain).unwrap();
+        cipher.encrypt_block(&mut block);
+        assert_eq!(to_hex(&block), expected);
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block, from_hex(plain).unwrap());
+    }
+
+    #[test]
+    fn fips_197_test() {
+        // FIPS-197, Appendix C
+        let plain = "00112233445566778899aabbccddeeff";
+        let key = from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .unwrap();
+        check(
+            &Aes128::new(&key[..16]).unwrap(),
+            plain,
+            "69C4E0D86A7B0430D8CDB78070B4C55A",
+        );
+        check(
+            &Aes192::new(&key[..24]).unwrap(),
+            plain,
+            "DDA97CA4864CDFE06EAF70A0EC0D7191",
+        );
+        check(
+            &Aes256::new(&key).unwrap(),
+            plain,
+            "8EA2B7CA516745BFEAFC49904B496089",
+        );
+    }
+
+    #[test]
+    fn challenge_vectors_test() {
+        let aes = Aes128::new(b"YELLOW SUBMARINE").unwrap();
+        let expected = include_str!("../../data/funky_music.txt").as_bytes();
+        let cipher = from_base64(include_str!("../../data/challenge7.txt").as_bytes()).unwrap();
+        assert_eq!(decrypt_ecb(&aes, &cipher).unwrap(), expected);
+        let cipher = from_base64(include_str!("../../data/challenge10.txt").as_bytes()).unwrap();
+        assert_eq!(decrypt_cbc(&aes, &cipher, &[0; 16]).unwrap(), expected);
+        assert_eq!(encrypt_cbc(&aes, expected, &[0; 16]).unwrap(), cipher);
+    }
+}
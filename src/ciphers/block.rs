@@ -0,0 +1,34 @@
+/// A keyed block cipher. Blocks are encrypted and decrypted in place.
+pub trait BlockCipher {
+    /// Block size in bytes.
+    fn block_size(&self) -> usize;
+
+    /// Encrypts a single block. Panics if the block has the wrong size.
+    fn encrypt_block(&self, block: &mut [u8]);
+
+    /// Decrypts a single block. Panics if the block has the wrong size.
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+impl<C: BlockCipher + ?Sized> BlockCipher for &C {
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        (**self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        (**self).decrypt_block(block)
+    }
+}
+
+// The default AES implementation is backed by OpenSSL if the "openssl" feature is enabled,
+// and by the pure-Rust implementation otherwise. Both key their contexts once per object, so
+// block operations do no per-call setup.
+#[cfg(feature = "openssl")]
+pub use crate::ciphers::openssl_aes::{Aes128, Aes192, Aes256};
+
+#[cfg(not(feature = "openssl"))]
+pub use crate::ciphers::aes::{Aes128, Aes192, Aes256};
@@ -0,0 +1,220 @@
+use crate::{
+    ciphers::block::BlockCipher,
+    error::{Error, Result},
+};
+
+/// The reduction polynomial x^128 + x^7 + x^2 + x + 1, in GCM's reflected bit order.
+const R: u128 = 0xE1 << 120;
+
+/// Multiplication in GF(2^128), as defined for GCM.
+/// Blocks are read as big-endian integers, and the most significant bit is the coefficient of x^0.
+pub fn gf128_mul(x: u128, y: u128) -> u128 {
+    let mut z = 0;
+    let mut v = y;
+    for i in (0..128).rev() {
+        if (x >> i) & 1 == 1 {
+            z ^= v;
+        }
+        v = if v & 1 == 1 { (v >> 1) ^ R } else { v >> 1 };
+    }
+    z
+}
+
+/// Converts a block of up to 16 bytes into a field element, padding with zeros on the right.
+pub fn to_field(block: &[u8]) -> u128 {
+    assert!(block.len() <= 16);
+    let mut buf = [0u8; 16];
+    buf[..block.len()].copy_from_slice(block);
+    u128::from_be_bytes(buf)
+}
+
+/// GHASH with hash key h over the data, zero-padded to a whole number of blocks.
+/// Computes the polynomial X_1 * h^m + X_2 * h^(m-1) + ... + X_m * h.
+pub fn ghash(h: u128, data: &[u8]) -> u128 {
+    data.chunks(16)
+        .fold(0, |acc, block| gf128_mul(acc ^ to_field(block), h))
+}
+
+/// GHASH over associated data and ciphertext as used in GCM: each is zero-padded, followed by a
+/// block with their lengths in bits.
+pub fn gcm_ghash(h: u128, aad: &[u8], cipher: &[u8]) -> u128 {
+    let lengths = ((aad.len() as u128 * 8) << 64) | (cipher.len() as u128 * 8);
+    let acc = ghash(h, aad);
+    let acc = cipher
+        .chunks(16)
+        .fold(acc, |acc, block| gf128_mul(acc ^ to_field(block), h));
+    gf128_mul(acc ^ lengths, h)
+}
+
+/// Increments the last 32 bits of the counter block, wrapping around.
+fn inc32(counter: u128) -> u128 {
+    let low = (counter as u32).wrapping_add(1);
+    (counter & !(u32::MAX as u128)) | low as u128
+}
+
+/// AES-GCM (or GCM over any 128-bit block cipher) authenticated encryption.
+pub struct Gcm<C: BlockCipher> {
+    cipher: C,
+    h: u128,
+}
+
+impl<C: BlockCipher> Gcm<C> {
+    /// Panics if the cipher does not have 16-byte blocks.
+    pub fn new(cipher: C) -> Self {
+        assert_eq!(
+            cipher.block_size(),
+            16,
+            "GCM requires a 128-bit block cipher"
+        );
+        let mut h = [0u8; 16];
+        cipher.encrypt_block(&mut h);
+        Self {
+            cipher,
+            h: u128::from_be_bytes(h),
+        }
+    }
+
+    /// The hash key H, the encryption of the zero block.
+    pub fn hash_key(&self) -> u128 {
+        self.h
+    }
+
+    fn encrypt_counter(&self, counter: u128) -> [u8; 16] {
+        let mut block = counter.to_be_bytes();
+        self.cipher.encrypt_block(&mut block);
+        block
+    }
+
+    /// The pre-counter block J0. 12-byte nonces are used directly, other lengths are hashed.
+    fn initial_counter(&self, nonce: &[u8]) -> Result<u128> {
+        if nonce.is_empty() {
+            return Err(Error::BadIvLength(0));
+        }
+        if nonce.len() == 12 {
+            return Ok(to_field(nonce) | 1);
+        }
+        Ok(gcm_ghash(self.h, &[], nonce))
+    }
+
+    /// Xors the text with the keystream starting from counter block inc32(j0).
+    fn apply_keystream(&self, j0: u128, text: &[u8]) -> Vec<u8> {
+        let mut counter = j0;
+        let mut res = Vec::with_capacity(text.len());
+        for block in text.chunks(16) {
+            counter = inc32(counter);
+            let keystream = self.encrypt_counter(counter);
+            res.extend(block.iter().zip(keystream).map(|(&x, y)| x ^ y));
+        }
+        res
+    }
+
+    fn tag(&self, j0: u128, aad: &[u8], cipher: &[u8]) -> [u8; 16] {
+        let s = gcm_ghash(self.h, aad, cipher);
+        let mask = self.encrypt_counter(j0);
+        (s ^ u128::from_be_bytes(mask)).to_be_bytes()
+    }
+
+    /// Encrypts the plaintext and authenticates it together with the associated data.
+    /// Returns the ciphertext and the 16-byte tag.
+    pub fn encrypt(&self, nonce: &[u8], plain: &[u8], aad: &[u8]) -> Result<(Vec<u8>, [u8; 16])> {
+        let j0 = self.initial_counter(nonce)?;
+        let cipher = self.apply_keystream(j0, plain);
+        let tag = self.tag(j0, aad, &cipher);
+        Ok((cipher, tag))
+    }
+
+    /// Verifies the tag, then decrypts the ciphertext.
+    /// Fails with Error::BadTag without decrypting if the tag does not match.
+    pub fn decrypt(&self, nonce: &[u8], cipher: &[u8], aad: &[u8], tag: &[u8]) -> Result<Vec<u8>> {
+        let j0 = self.initial_counter(nonce)?;
+        let expected = self.tag(j0, aad, cipher);
+        let diff = expected
+            .iter()
+            .zip(tag)
+            .fold(0, |acc, (&x, &y)| acc | (x ^ y));
+        if tag.len() != 16 || diff != 0 {
+            return Err(Error::BadTag);
+        }
+        Ok(self.apply_keystream(j0, cipher))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        ciphers::Aes128,
+        convert::{from_hex, to_hex},
+    };
+
+    use super::*;
+
+    #[test]
+    fn ghash_test() {
+        // Test case 2 from the GCM specification
+        let gcm = Gcm::new(Aes128::new(&[0; 16]).unwrap());
+        assert_eq!(gcm.hash_key(), 0x66e94bd4ef8a2c3b884cfa59ca342b2e);
+        let cipher = from_hex("0388dace60b6a392f328c2b971b2fe78").unwrap();
+        assert_eq!(
+            gcm_ghash(gcm.hash_key(), &[], &cipher),
+            0xf38cbb1ad69223dcc3457ae5b6b0f885
+        );
+        // The most significant bit is the coefficient of x^0, so 1 << 127 is the identity.
+        let x = 0x0123456789abcdef_u128;
+        assert_eq!(gf128_mul(x, 1 << 127), x);
+        assert_eq!(gf128_mul(x, gcm.hash_key()), gf128_mul(gcm.hash_key(), x));
+    }
+
+    #[test]
+    fn gcm_test() {
+        let gcm = Gcm::new(Aes128::new(&[0; 16]).unwrap());
+        let (cipher, tag) = gcm.encrypt(&[0; 12], &[], &[]).unwrap();
+        assert!(cipher.is_empty());
+        assert_eq!(to_hex(&tag), "58E2FCCEFA7E3061367F1D57A4E7455A");
+        let (cipher, tag) = gcm.encrypt(&[0; 12], &[0; 16], &[]).unwrap();
+        assert_eq!(to_hex(&cipher), "0388DACE60B6A392F328C2B971B2FE78");
+        assert_eq!(to_hex(&tag), "AB6E47D42CEC13BDF53A67B21257BDDF");
+
+        // Test cases 4 and 6
+        let gcm =
+            Gcm::new(Aes128::new(&from_hex("feffe9928665731c6d6a8f9467308308").unwrap()).unwrap());
+        let plain = from_hex(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        )
+        .unwrap();
+        let aad = from_hex("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let nonce = from_hex("cafebabefacedbaddecaf888").unwrap();
+        let (cipher, tag) = gcm.encrypt(&nonce, &plain, &aad).unwrap();
+        assert_eq!(
+            to_hex(&cipher),
+            "42831EC2217774244B7221B784D0D49CE3AA212F2C02A4E035C17E2329ACA12E\
+             21D514B25466931C7D8F6A5AAC84AA051BA30B396A0AAC973D58E091"
+        );
+        assert_eq!(to_hex(&tag), "5BC94FBC3221A5DB94FAE95AE7121A47");
+        assert_eq!(gcm.decrypt(&nonce, &cipher, &aad, &tag).unwrap(), plain);
+
+        let nonce = from_hex(
+            "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728\
+             c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+        )
+        .unwrap();
+        let (cipher, tag) = gcm.encrypt(&nonce, &plain, &aad).unwrap();
+        assert_eq!(
+            to_hex(&cipher),
+            "8CE24998625615B603A033ACA13FB894BE9112A5C3A211A8BA262A3CCA7E2CA7\
+             01E4A9A4FBA43C90CCDCB281D48C7C6FD62875D2ACA417034C34AEE5"
+        );
+        assert_eq!(to_hex(&tag), "619CC5AEFFFE0BFA462AF43C1699D050");
+
+        let mut bad_tag = tag;
+        bad_tag[0] ^= 1;
+        assert!(matches!(
+            gcm.decrypt(&nonce, &cipher, &aad, &bad_tag),
+            Err(Error::BadTag)
+        ));
+        assert!(matches!(
+            gcm.decrypt(&nonce, &cipher, &aad[1..], &tag),
+            Err(Error::BadTag)
+        ));
+    }
+}
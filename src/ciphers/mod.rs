@@ -0,0 +1,496 @@
+pub mod aes;
+pub mod block;
+pub mod gcm;
+pub mod modes;
+#[cfg(feature = "openssl")]
+pub mod openssl_aes;
+pub mod stream;
+
+use crate::{
+    error::{Error, Result},
+    freq::Scorer,
+    mersenne::MT19937,
+    util::hamming_distance,
+};
+
+pub use block::{Aes128, Aes192, Aes256, BlockCipher};
+pub use gcm::Gcm;
+pub use modes::{
+    decrypt_cbc, decrypt_cbc_cts, decrypt_cfb, decrypt_cfb8, decrypt_ecb, decrypt_pcbc,
+    encrypt_cbc, encrypt_cbc_cts, encrypt_cfb, encrypt_cfb8, encrypt_ctr, encrypt_ecb, encrypt_ofb,
+    encrypt_pcbc, Cbc, CounterLayout, Cts, Ecb, Endian, CTR,
+};
+pub use stream::{
+    CbcDecryptor, CbcEncryptor, CtrStream, Mt19937Stream, Transform, TransformReader,
+    TransformWriter,
+};
+
+/// Returns the xor of equal-length slices 'a' and 'b'.
+pub fn fixed_xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b.iter()).map(|(&x, &y)| x ^ y).collect()
+}
+
+/// Returns the xor of 'a' with 'b' repeated cyclically.
+pub fn repeating_xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter()
+        .zip(b.iter().cycle())
+        .map(|(&x, &y)| x ^ y)
+        .collect()
+}
+
+/// Decrypts a single-character xor cipher by choosing the key whose decryption has the best
+/// (lowest) score. Returns the decrypted text and the score.
+/// Returns an empty text and f64::MAX if the scorer rejects every key with an infinite score.
+pub fn decrypt_caesar<S: Scorer + ?Sized>(a: &[u8], scorer: &S) -> (Vec<u8>, f64) {
+    let mut best = vec![];
+    let mut best_score = f64::MAX;
+    for x in 0..=255 {
+        let tmp = repeating_xor(a, &[x]);
+        let s = scorer.score(&tmp);
+        if s < best_score {
+            best = tmp;
+            best_score = s
+        }
+    }
+    (best, best_score)
+}
+
+/// Attempts to decrypt a list of texts, returns the one with the best score.
+pub fn multiple_decrypt_caesar<'a, I, S>(texts: I, scorer: &S) -> (Vec<u8>, f64)
+where
+    I: Iterator<Item = &'a [u8]>,
+    S: Scorer + ?Sized,
+{
+    let mut best = vec![];
+    let mut best_score = f64::MAX;
+    for a in texts {
+        let (tmp, s) = decrypt_caesar(a, scorer);
+        if s < best_score {
+            best = tmp;
+            best_score = s
+        }
+    }
+    (best, best_score)
+}
+
+/// Attempts to decrypt a Vigenere-encrypted text with a fixed keysize
+/// Returns the best candidate and score
+/// If the scorer rejects every decryption of some column, returns an empty text and f64::MAX.
+pub fn decrypt_vigenere_fixed<S: Scorer + ?Sized>(
+    s: &[u8],
+    keysize: usize,
+    scorer: &S,
+) -> (Vec<u8>, f64) {
+    let mut blocks = vec![vec![]; keysize];
+    let n = s.len();
+    for i in 0..n {
+        blocks[i % keysize].push(s[i]);
+    }
+    let mut avg_score = 0.0;
+    let mut decrypted = vec![0u8; n];
+    for k in 0..keysize {
+        let (dec_block, score) = decrypt_caesar(&blocks[k], scorer);
+        if dec_block.len() != blocks[k].len() {
+            return (vec![], f64::MAX);
+        }
+        avg_score += score / keysize as f64;
+        for i in 0..blocks[k].len() {
+            decrypted[i * keysize + k] = dec_block[i];
+        }
+    }
+    (decrypted, avg_score)
+}
+
+/// Ranks the keysizes from 1 to max_keysize of a repeating-key xor cipher, most likely first.
+/// Returns the keysizes with their scores, lower is better.
+///
+/// The score is the Hamming distance between consecutive keysize-long blocks, per bit and
+/// averaged over all the pairs, divided by the index of coincidence of the columns. With the
+/// right keysize, both blocks of a pair are xored with the same key, so the distance is that of
+/// the plaintexts, and each column is a single-byte xor of text, which keeps its coincidences.
+pub fn rank_vigenere_keysizes(s: &[u8], max_keysize: usize) -> Vec<(usize, f64)> {
+    let mut res = vec![];
+    for keysize in 1..=max_keysize.min(s.len() / 2) {
+        let blocks: Vec<_> = s.chunks_exact(keysize).collect();
+        let pairs = blocks.len() - 1;
+        let distance = blocks
+            .windows(2)
+            .map(|w| hamming_distance(w[0], w[1]) as f64)
+            .sum::<f64>()
+            / (pairs * keysize * 8) as f64;
+        res.push((keysize, distance / index_of_coincidence(s, keysize)));
+    }
+    res.sort_by(|a, b| a.1.total_cmp(&b.1));
+    res
+}
+
+/// The probability that two bytes from the same column (positions equal modulo keysize) are
+/// equal, averaged over the columns.
+fn index_of_coincidence(s: &[u8], keysize: usize) -> f64 {
+    let mut total = 0.0;
+    for k in 0..keysize {
+        let mut counts = [0usize; 256];
+        let mut n = 0;
+        for &c in s.iter().skip(k).step_by(keysize) {
+            counts[c as usize] += 1;
+            n += 1;
+        }
+        if n > 1 {
+            let same: usize = counts.iter().map(|&c| c * c.saturating_sub(1)).sum();
+            total += same as f64 / (n * (n - 1)) as f64;
+        }
+    }
+    // Avoid dividing by zero when nothing coincides.
+    total.max(f64::MIN_POSITIVE) / keysize as f64
+}
+
+/// The shortest key that repeats to the given one.
+fn shortest_period(key: &[u8]) -> &[u8] {
+    let n = key.len();
+    let period = (1..n)
+        .filter(|&p| n.is_multiple_of(p))
+        .find(|&p| (p..n).all(|i| key[i] == key[i - p]))
+        .unwrap_or(n);
+    &key[..period]
+}
+
+/// Breaks a repeating-key xor cipher with an unknown key of at most max_keysize bytes.
+/// The `tries` best-ranked keysizes are each decrypted with decrypt_vigenere_fixed.
+/// Returns the candidates (key, plaintext, score), best first. A key that repeats a shorter one
+/// is reported once, as the shorter key.
+pub fn break_repeating_xor<S: Scorer + ?Sized>(
+    s: &[u8],
+    max_keysize: usize,
+    tries: usize,
+    scorer: &S,
+) -> Vec<(Vec<u8>, Vec<u8>, f64)> {
+    let mut res: Vec<(Vec<u8>, Vec<u8>, f64)> = vec![];
+    let ranking = rank_vigenere_keysizes(s, max_keysize);
+    for &(keysize, _) in ranking.iter().take(tries) {
+        let (plain, score) = decrypt_vigenere_fixed(s, keysize, scorer);
+        if plain.is_empty() {
+            continue;
+        }
+        let key = fixed_xor(&s[..keysize], &plain[..keysize]);
+        let key = shortest_period(&key).to_vec();
+        if res.iter().any(|(k, _, _)| *k == key) {
+            continue;
+        }
+        res.push((key, plain, score));
+    }
+    res.sort_by(|a, b| a.2.total_cmp(&b.2));
+    res
+}
+
+/// Improves a repeating-key xor key by scoring the whole decrypted text rather than each column
+/// on its own, which lets scorers that look at neighbouring bytes, like n-gram models, correct
+/// the key of a short ciphertext. Each key byte in turn is set to the value with the best score,
+/// until no single change improves it. Returns the key, the plaintext and its score.
+pub fn refine_repeating_xor_key<S: Scorer + ?Sized>(
+    s: &[u8],
+    key: &[u8],
+    scorer: &S,
+) -> (Vec<u8>, Vec<u8>, f64) {
+    let mut key = key.to_vec();
+    let mut best_score = scorer.score(&repeating_xor(s, &key));
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..key.len() {
+            let mut best = key[i];
+            for x in 0..=255 {
+                key[i] = x;
+                let score = scorer.score(&repeating_xor(s, &key));
+                if score < best_score {
+                    best = x;
+                    best_score = score;
+                    improved = true;
+                }
+            }
+            key[i] = best;
+        }
+    }
+    let plain = repeating_xor(s, &key);
+    (key, plain, best_score)
+}
+
+/// Recovers the keystream shared by texts encrypted with a fixed-nonce CTR (or any reused
+/// keystream), one byte per column, for the full length of the longest text. Each byte is chosen
+/// like in decrypt_caesar, from the texts that are long enough, so the tail uses fewer samples.
+///
+/// Returns each keystream byte with a confidence between 0 and 1. It is the probability of the
+/// chosen byte when the score times the number of samples is taken as a negative log-likelihood,
+/// which is exact for LogLikelihood and n-gram scorers, and a rough guide for the others.
+pub fn recover_fixed_nonce_keystream<T, S>(texts: &[T], scorer: &S) -> Vec<(u8, f64)>
+where
+    T: AsRef<[u8]>,
+    S: Scorer + ?Sized,
+{
+    recover_fixed_nonce_keystream_by(texts, |_, column| scorer.score(column))
+}
+
+/// Like recover_fixed_nonce_keystream, but each candidate column is scored by
+/// score(position, column), so that the score can depend on the position in the texts, e.g. to
+/// expect capitals at the start of sentences.
+pub fn recover_fixed_nonce_keystream_by<T, F>(texts: &[T], score: F) -> Vec<(u8, f64)>
+where
+    T: AsRef<[u8]>,
+    F: Fn(usize, &[u8]) -> f64,
+{
+    let max_len = texts.iter().map(|t| t.as_ref().len()).max().unwrap_or(0);
+    let mut res = vec![];
+    for i in 0..max_len {
+        let column: Vec<u8> = texts
+            .iter()
+            .filter_map(|t| t.as_ref().get(i))
+            .copied()
+            .collect();
+        let scores: Vec<f64> = (0..=255)
+            .map(|x| score(i, &repeating_xor(&column, &[x])))
+            .collect();
+        let (best, &best_score) = scores
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let confidence = if best_score.is_finite() {
+            let n = column.len() as f64;
+            1.0 / scores
+                .iter()
+                .map(|&s| (-n * (s - best_score)).exp())
+                .sum::<f64>()
+        } else {
+            0.0
+        };
+        res.push((best as u8, confidence));
+    }
+    res
+}
+
+/// Decrypts texts encrypted with the same CTR keystream, using recover_fixed_nonce_keystream.
+/// Returns each plaintext with the confidence of each of its bytes.
+pub fn break_fixed_nonce_ctr<T, S>(texts: &[T], scorer: &S) -> Vec<(Vec<u8>, Vec<f64>)>
+where
+    T: AsRef<[u8]>,
+    S: Scorer + ?Sized,
+{
+    break_fixed_nonce_ctr_by(texts, |_, column| scorer.score(column))
+}
+
+/// Like break_fixed_nonce_ctr, with a score that depends on the position, as in
+/// recover_fixed_nonce_keystream_by.
+pub fn break_fixed_nonce_ctr_by<T, F>(texts: &[T], score: F) -> Vec<(Vec<u8>, Vec<f64>)>
+where
+    T: AsRef<[u8]>,
+    F: Fn(usize, &[u8]) -> f64,
+{
+    let keystream = recover_fixed_nonce_keystream_by(texts, score);
+    texts
+        .iter()
+        .map(|t| {
+            t.as_ref()
+                .iter()
+                .zip(&keystream)
+                .map(|(&c, &(k, confidence))| (c ^ k, confidence))
+                .unzip()
+        })
+        .collect()
+}
+
+// The aes_128 helpers below expand the key on every call. To process many messages under the
+// same key, construct an Aes128 once and use the functions in the modes module instead.
+
+/// Decrypt a text with aes 128 in ECB mode.
+/// Removes padding in the output.
+pub fn decrypt_aes_128_ecb(s: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    decrypt_ecb(&Aes128::new(key)?, s)
+}
+
+/// Encrypt a text with aes 128 in ECB mode.
+pub fn encrypt_aes_128_ecb(s: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    Ok(encrypt_ecb(&Aes128::new(key)?, s))
+}
+
+/// Decrypts a single block of bytes
+pub fn decrypt_aes_128_block(block: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if block.len() != 16 {
+        return Err(Error::BadInputLength(block.len()));
+    }
+    let mut res = block.to_vec();
+    Aes128::new(key)?.decrypt_block(&mut res);
+    Ok(res)
+}
+
+/// Decrypt a text with aes 128 in CBC mode.
+/// Removes padding in the output.
+pub fn decrypt_aes_128_cbc(s: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    decrypt_cbc(&Aes128::new(key)?, s, iv)
+}
+
+/// Encrypts a single block of bytes
+pub fn encrypt_aes_128_block(block: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if block.len() != 16 {
+        return Err(Error::BadInputLength(block.len()));
+    }
+    let mut res = block.to_vec();
+    Aes128::new(key)?.encrypt_block(&mut res);
+    Ok(res)
+}
+
+/// Encrypt a text with aes 128 in CBC mode.
+/// Pads the input using PKCS#7 algorithm.
+pub fn encrypt_aes_128_cbc(s: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    encrypt_cbc(&Aes128::new(key)?, s, iv)
+}
+
+/// Encrypts a text in ctr mode. Can also be used for decryption.
+/// Requires a 16-byte secret key and an 8-byte nonce.
+pub fn encrypt_aes_128_ctr(text: &[u8], secret_key: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
+    encrypt_ctr(&Aes128::new(secret_key)?, text, nonce)
+}
+
+pub fn mt19937_stream_cipher(text: &[u8], secret_key: u32) -> Vec<u8> {
+    let ctr = MT19937::new(secret_key);
+    let mut res = vec![];
+    for (w, k) in text.chunks(4).zip(ctr) {
+        for (i, &x) in w.iter().enumerate() {
+            res.push(x ^ (k >> (8 * i)) as u8);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        convert::{from_base64, from_hex, to_hex},
+        freq::{LogLikelihood, L1},
+    };
+
+    use super::*;
+
+    #[test]
+    fn decrypt_vigenere_fixed_test() {
+        let raw_data = include_str!("../../data/challenge6.txt");
+        let text = from_base64(raw_data.as_bytes()).unwrap();
+        let expected = include_str!("../../data/funky_music.txt");
+        let (decrypted, _) = decrypt_vigenere_fixed(&text, 29, &L1::english());
+        let secret_key: String = 
+            (0..29)
+            .map(|i| (text[i] ^ decrypted[i]) as char)
+            .collect();
+        assert_eq!(&secret_key, "Terminator X: Bring the noise");
+        assert_eq!(decrypted, expected.as_bytes());
+    }
+
+    #[test]
+    fn break_repeating_xor_test() {
+        let plain = include_str!("../../data/funky_music.txt").as_bytes();
+        let key = b"ICE";
+        let cipher = repeating_xor(plain, key);
+        let ranking = rank_vigenere_keysizes(&cipher, 40);
+        assert_eq!(ranking.len(), 40);
+        assert_eq!(ranking[0].0 % 3, 0);
+        let candidates = break_repeating_xor(&cipher, 40, 5, &L1::english());
+        assert_eq!(candidates[0].0, key);
+        assert_eq!(candidates[0].1, plain);
+        assert!(candidates.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert_eq!(shortest_period(b"abcabc"), b"abc");
+        assert_eq!(shortest_period(b"abcab"), b"abcab");
+    }
+
+    #[test]
+    fn binary_plaintext_test() {
+        // Mostly text, with some bytes outside ASCII that the English L1 scorer cannot accept.
+        let mut plain = include_str!("../../data/funky_music.txt").as_bytes()[..600].to_vec();
+        for i in (0..plain.len()).step_by(50) {
+            plain[i] = 0xff;
+        }
+        let cipher = repeating_xor(&plain, b"xor");
+        let (decrypted, score) = decrypt_vigenere_fixed(&cipher, 3, &L1::english());
+        assert!(decrypted.is_empty());
+        assert_eq!(score, f64::MAX);
+        let (decrypted, _) = decrypt_vigenere_fixed(&cipher, 3, &LogLikelihood::english());
+        assert_eq!(decrypted, plain);
+    }
+
+    #[test]
+    fn fixed_nonce_keystream_test() {
+        let keystream: Vec<u8> = (0..100).map(|i| (i * 37 + 11) as u8).collect();
+        let texts: Vec<Vec<u8>> = include_str!("../../data/funky_music.txt")
+            .lines()
+            .map(|line| fixed_xor(line.as_bytes(), &keystream[..line.len()]))
+            .collect();
+        let recovered = recover_fixed_nonce_keystream(&texts, &LogLikelihood::english());
+        let max_len = texts.iter().map(|t| t.len()).max().unwrap();
+        assert_eq!(recovered.len(), max_len);
+        for i in 1..20 {
+            assert_eq!(recovered[i].0, keystream[i]);
+            assert!(recovered[i].1 > 0.99);
+        }
+        // The last column comes from a single text.
+        assert!(recovered[max_len - 1].1 < 0.99);
+
+        // Most lines start with a capital, which unigram statistics take for lower case unless
+        // the first column is scored as if its case were swapped.
+        let english = LogLikelihood::english();
+        assert_ne!(recovered[0].0, keystream[0]);
+        let recovered = recover_fixed_nonce_keystream_by(&texts, |i, column| {
+            if i == 0 {
+                english.score(&swap_case(column))
+            } else {
+                english.score(column)
+            }
+        });
+        assert_eq!(recovered[0].0, keystream[0]);
+        assert_eq!(recovered[1].0, keystream[1]);
+    }
+
+    fn swap_case(s: &[u8]) -> Vec<u8> {
+        s.iter()
+            .map(|&c| if c.is_ascii_alphabetic() { c ^ 0x20 } else { c })
+            .collect()
+    }
+
+    #[test]
+    fn cbc_errors_test() {
+        let key = b"YELLOW SUBMARINE";
+        let iv = [0u8; 16];
+        let cipher = encrypt_aes_128_cbc(b"ICE ICE BABY", key, &iv).unwrap();
+        assert_eq!(decrypt_aes_128_cbc(&cipher, key, &iv).unwrap(), b"ICE ICE BABY");
+        assert!(matches!(
+            decrypt_aes_128_cbc(&cipher, &key[..15], &iv),
+            Err(Error::BadKeyLength(15))
+        ));
+        assert!(matches!(
+            decrypt_aes_128_cbc(&cipher, key, &iv[..8]),
+            Err(Error::BadIvLength(8))
+        ));
+        assert!(matches!(
+            decrypt_aes_128_cbc(&cipher[..15], key, &iv),
+            Err(Error::BadInputLength(15))
+        ));
+        let mut tampered_iv = iv;
+        tampered_iv[15] ^= 0xFF;
+        assert!(matches!(
+            decrypt_aes_128_cbc(&cipher, key, &tampered_iv),
+            Err(Error::BadPadding)
+        ));
+    }
+
+    #[test]
+    fn aes_128_block_test() {
+        // FIPS-197, Appendix C.1. The plaintext is not PKCS#7 padded, so decrypting the single
+        // block must not strip or hold back anything.
+        let key = from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let plain = from_hex("00112233445566778899aabbccddeeff").unwrap();
+        let cipher = encrypt_aes_128_block(&plain, &key).unwrap();
+        assert_eq!(to_hex(&cipher), "69C4E0D86A7B0430D8CDB78070B4C55A");
+        assert_eq!(decrypt_aes_128_block(&cipher, &key).unwrap(), plain);
+        assert!(matches!(
+            decrypt_aes_128_block(&cipher[..15], &key),
+            Err(Error::BadInputLength(15))
+        ));
+    }
+}
@@ -0,0 +1,629 @@
+use crate::{
+    ciphers::{block::BlockCipher, fixed_xor},
+    error::{Error, Result},
+    util::{pad, unpad_in_place},
+};
+
+fn check_iv<C: BlockCipher>(cipher: &C, iv: &[u8]) -> Result<()> {
+    if iv.len() != cipher.block_size() {
+        return Err(Error::BadIvLength(iv.len()));
+    }
+    Ok(())
+}
+
+fn check_blocks<C: BlockCipher>(cipher: &C, s: &[u8]) -> Result<()> {
+    if !s.len().is_multiple_of(cipher.block_size()) {
+        return Err(Error::BadInputLength(s.len()));
+    }
+    Ok(())
+}
+
+/// Encrypts a text in ECB mode.
+/// Pads the input using PKCS#7 algorithm.
+pub fn encrypt_ecb<C: BlockCipher>(cipher: &C, s: &[u8]) -> Vec<u8> {
+    let mut res = pad(s, cipher.block_size());
+    for block in res.chunks_mut(cipher.block_size()) {
+        cipher.encrypt_block(block);
+    }
+    res
+}
+
+/// Decrypts a text in ECB mode.
+/// Removes padding in the output.
+pub fn decrypt_ecb<C: BlockCipher>(cipher: &C, s: &[u8]) -> Result<Vec<u8>> {
+    check_blocks(cipher, s)?;
+    let mut res = s.to_vec();
+    for block in res.chunks_mut(cipher.block_size()) {
+        cipher.decrypt_block(block);
+    }
+    unpad_in_place(&mut res)?;
+    Ok(res)
+}
+
+/// CBC encryption of a whole number of blocks, without padding.
+fn cbc_encrypt_blocks<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Vec<u8> {
+    let mut res = vec![];
+    let mut prev_block = iv.to_vec();
+    for block in s.chunks(cipher.block_size()) {
+        let mut block = fixed_xor(block, &prev_block);
+        cipher.encrypt_block(&mut block);
+        res.extend_from_slice(&block);
+        prev_block = block;
+    }
+    res
+}
+
+/// CBC decryption of a whole number of blocks, without padding.
+fn cbc_decrypt_blocks<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Vec<u8> {
+    let mut res = vec![];
+    let mut prev_block = iv;
+    for block in s.chunks(cipher.block_size()) {
+        let mut plain = block.to_vec();
+        cipher.decrypt_block(&mut plain);
+        res.extend_from_slice(&fixed_xor(&plain, prev_block));
+        prev_block = block;
+    }
+    res
+}
+
+/// Encrypts a text in CBC mode.
+/// Pads the input using PKCS#7 algorithm.
+pub fn encrypt_cbc<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    Ok(cbc_encrypt_blocks(cipher, &pad(s, cipher.block_size()), iv))
+}
+
+/// Decrypts a text in CBC mode.
+/// Removes padding in the output.
+pub fn decrypt_cbc<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    check_blocks(cipher, s)?;
+    let mut res = cbc_decrypt_blocks(cipher, s, iv);
+    unpad_in_place(&mut res)?;
+    Ok(res)
+}
+
+/// Encrypts a text in PCBC mode: each plaintext block is xored with both the previous plaintext
+/// and ciphertext blocks before encryption.
+/// Pads the input using PKCS#7 algorithm.
+pub fn encrypt_pcbc<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let mut res = vec![];
+    let mut chain = iv.to_vec();
+    for block in pad(s, cipher.block_size()).chunks(cipher.block_size()) {
+        let mut enc = fixed_xor(block, &chain);
+        cipher.encrypt_block(&mut enc);
+        chain = fixed_xor(block, &enc);
+        res.extend_from_slice(&enc);
+    }
+    Ok(res)
+}
+
+/// Decrypts a text in PCBC mode.
+/// Removes padding in the output.
+pub fn decrypt_pcbc<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    check_blocks(cipher, s)?;
+    let mut res = vec![];
+    let mut chain = iv.to_vec();
+    for block in s.chunks(cipher.block_size()) {
+        let mut plain = block.to_vec();
+        cipher.decrypt_block(&mut plain);
+        let plain = fixed_xor(&plain, &chain);
+        chain = fixed_xor(&plain, block);
+        res.extend_from_slice(&plain);
+    }
+    unpad_in_place(&mut res)?;
+    Ok(res)
+}
+
+/// Ciphertext stealing variants for CBC, as in the addendum to NIST SP 800-38A.
+/// They differ only in the order of the last two ciphertext blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cts {
+    /// The truncated second-to-last block comes first.
+    Cs1,
+    /// Like CS3, unless the input is a whole number of blocks, when it is plain CBC.
+    Cs2,
+    /// The last two blocks are always swapped (as in Kerberos, RFC 3962).
+    Cs3,
+}
+
+/// Encrypts a text in CBC mode with ciphertext stealing. The ciphertext has the same length as the
+/// plaintext, so no padding is needed, but the input must be at least one block long.
+pub fn encrypt_cbc_cts<C: BlockCipher>(
+    cipher: &C,
+    s: &[u8],
+    iv: &[u8],
+    variant: Cts,
+) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let b = cipher.block_size();
+    if s.len() < b {
+        return Err(Error::BadInputLength(s.len()));
+    }
+    if s.len() == b {
+        return Ok(cbc_encrypt_blocks(cipher, s, iv));
+    }
+    // Length of the last, possibly partial, block.
+    let d = s.len() - (s.len() - 1) / b * b;
+    let mut padded = s.to_vec();
+    padded.resize(s.len() + b - d, 0);
+    let mut res = cbc_encrypt_blocks(cipher, &padded, iv);
+    let n = res.len();
+    let last = res.split_off(n - b);
+    let second_last = res.split_off(n - 2 * b);
+    if variant == Cts::Cs1 || (variant == Cts::Cs2 && d == b) {
+        res.extend_from_slice(&second_last[..d]);
+        res.extend_from_slice(&last);
+    } else {
+        res.extend_from_slice(&last);
+        res.extend_from_slice(&second_last[..d]);
+    }
+    Ok(res)
+}
+
+/// Decrypts a text in CBC mode with ciphertext stealing.
+pub fn decrypt_cbc_cts<C: BlockCipher>(
+    cipher: &C,
+    s: &[u8],
+    iv: &[u8],
+    variant: Cts,
+) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let b = cipher.block_size();
+    if s.len() < b {
+        return Err(Error::BadInputLength(s.len()));
+    }
+    if s.len() == b {
+        return Ok(cbc_decrypt_blocks(cipher, s, iv));
+    }
+    let d = s.len() - (s.len() - 1) / b * b;
+    let n = s.len();
+    let (head, tail) = s.split_at(n - b - d);
+    // The truncated second-to-last block, and the last block, in CS1 order.
+    let (partial, last) = if variant == Cts::Cs1 || (variant == Cts::Cs2 && d == b) {
+        tail.split_at(d)
+    } else {
+        let (last, partial) = tail.split_at(b);
+        (partial, last)
+    };
+    // The last block was encrypted from the zero-padded plaintext, so its decryption reveals the
+    // stolen tail of the second-to-last block.
+    let mut z = last.to_vec();
+    cipher.decrypt_block(&mut z);
+    let mut second_last = partial.to_vec();
+    second_last.extend_from_slice(&z[d..]);
+    let mut blocks = head.to_vec();
+    blocks.extend_from_slice(&second_last);
+    let mut res = cbc_decrypt_blocks(cipher, &blocks, iv);
+    res.extend(z[..d].iter().zip(partial).map(|(&x, &y)| x ^ y));
+    Ok(res)
+}
+
+/// ECB mode over a keyed block cipher.
+pub struct Ecb<C: BlockCipher> {
+    cipher: C,
+}
+
+impl<C: BlockCipher> Ecb<C> {
+    pub fn new(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    pub fn encrypt(&self, s: &[u8]) -> Vec<u8> {
+        encrypt_ecb(&self.cipher, s)
+    }
+
+    pub fn decrypt(&self, s: &[u8]) -> Result<Vec<u8>> {
+        decrypt_ecb(&self.cipher, s)
+    }
+}
+
+/// CBC mode over a keyed block cipher. The IV is given per message.
+pub struct Cbc<C: BlockCipher> {
+    cipher: C,
+}
+
+impl<C: BlockCipher> Cbc<C> {
+    pub fn new(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    pub fn encrypt(&self, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+        encrypt_cbc(&self.cipher, s, iv)
+    }
+
+    pub fn decrypt(&self, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+        decrypt_cbc(&self.cipher, s, iv)
+    }
+}
+
+/// Byte order of the CTR counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Layout of the CTR counter block: the nonce, followed by the counter in the remaining bytes.
+/// The counter wraps around within its own bytes and never carries into the nonce.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CounterLayout {
+    /// Length of the nonce in bytes.
+    pub nonce_len: usize,
+    /// Byte order of the counter.
+    pub endian: Endian,
+    /// Counter value for the first keystream block.
+    pub initial: u128,
+}
+
+impl CounterLayout {
+    /// RFC 3686: a 4-byte nonce and an 8-byte IV, then a big-endian 32-bit counter starting at 1.
+    pub const RFC_3686: Self = Self {
+        nonce_len: 12,
+        endian: Endian::Big,
+        initial: 1,
+    };
+
+    /// GCM with a 96-bit nonce: a big-endian 32-bit counter. Counter 1 is reserved for the tag,
+    /// so the keystream starts at 2.
+    pub const GCM: Self = Self {
+        nonce_len: 12,
+        endian: Endian::Big,
+        initial: 2,
+    };
+
+    /// The layout used by the challenges: the nonce is the first half of the block, the second
+    /// half is a little-endian counter starting from 0.
+    pub fn half_block(block_size: usize) -> Self {
+        Self {
+            nonce_len: block_size / 2,
+            endian: Endian::Little,
+            initial: 0,
+        }
+    }
+}
+
+/// CTR mode keystream, one block at a time.
+pub struct CTR<C: BlockCipher> {
+    cipher: C,
+    nonce: Vec<u8>,
+    layout: CounterLayout,
+    /// Size of the counter in bytes.
+    width: usize,
+    counter: u128,
+}
+
+impl<C: BlockCipher> Iterator for CTR<C> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut res = self.nonce.clone();
+        match self.layout.endian {
+            Endian::Little => res.extend_from_slice(&self.counter.to_le_bytes()[..self.width]),
+            Endian::Big => res.extend_from_slice(&self.counter.to_be_bytes()[16 - self.width..]),
+        }
+        self.cipher.encrypt_block(&mut res);
+        self.counter = self.counter.wrapping_add(1) & self.mask();
+        Some(res)
+    }
+}
+
+impl<C: BlockCipher> CTR<C> {
+    /// Uses the layout of the challenges: the nonce must be half the block size.
+    pub fn new(cipher: C, nonce: &[u8]) -> Result<Self> {
+        let layout = CounterLayout::half_block(cipher.block_size());
+        Self::with_layout(cipher, nonce, layout)
+    }
+
+    /// The nonce must have the length given by the layout, and the counter must fit in 16 bytes.
+    pub fn with_layout(cipher: C, nonce: &[u8], layout: CounterLayout) -> Result<Self> {
+        let block_size = cipher.block_size();
+        if nonce.len() != layout.nonce_len || nonce.len() >= block_size {
+            return Err(Error::BadIvLength(nonce.len()));
+        }
+        let width = block_size - nonce.len();
+        if width > 16 {
+            return Err(Error::BadIvLength(nonce.len()));
+        }
+        let mut res = Self {
+            cipher,
+            nonce: nonce.to_vec(),
+            layout,
+            width,
+            counter: 0,
+        };
+        res.seek_block(0);
+        Ok(res)
+    }
+
+    fn mask(&self) -> u128 {
+        u128::MAX >> (128 - 8 * self.width)
+    }
+
+    /// Moves to the given keystream block, counting from the first one.
+    pub fn seek_block(&mut self, block: u64) {
+        self.counter = self.layout.initial.wrapping_add(block as u128) & self.mask();
+    }
+}
+
+/// Encrypts a text in CTR mode. Can also be used for decryption.
+pub fn encrypt_ctr<C: BlockCipher>(cipher: &C, text: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
+    let block_size = cipher.block_size();
+    let ctr = CTR::new(cipher, nonce)?;
+    let mut res = vec![];
+    for (w, k) in text.chunks(block_size).zip(ctr) {
+        res.extend(w.iter().zip(k).map(|(&a, b)| a ^ b));
+    }
+    Ok(res)
+}
+
+/// Encrypts a text in CFB mode, with a full block as the feedback segment.
+/// No padding is needed: a final partial block uses only part of the keystream.
+pub fn encrypt_cfb<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let mut res = vec![];
+    let mut feedback = iv.to_vec();
+    for block in s.chunks(cipher.block_size()) {
+        cipher.encrypt_block(&mut feedback);
+        let enc: Vec<u8> = block.iter().zip(&feedback).map(|(&x, &y)| x ^ y).collect();
+        res.extend_from_slice(&enc);
+        feedback = enc;
+    }
+    Ok(res)
+}
+
+/// Decrypts a text in CFB mode, with a full block as the feedback segment.
+pub fn decrypt_cfb<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let mut res = vec![];
+    let mut feedback = iv.to_vec();
+    for block in s.chunks(cipher.block_size()) {
+        cipher.encrypt_block(&mut feedback);
+        res.extend(block.iter().zip(&feedback).map(|(&x, &y)| x ^ y));
+        feedback = block.to_vec();
+    }
+    Ok(res)
+}
+
+/// Runs CFB mode with an 8-bit feedback segment: one block encryption per byte.
+fn cfb8<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let mut res = vec![];
+    let mut register = iv.to_vec();
+    for &c in s {
+        let mut keystream = register.clone();
+        cipher.encrypt_block(&mut keystream);
+        let out = c ^ keystream[0];
+        register.rotate_left(1);
+        *register.last_mut().unwrap() = if encrypt { out } else { c };
+        res.push(out);
+    }
+    Ok(res)
+}
+
+/// Encrypts a text in CFB mode with an 8-bit feedback segment.
+pub fn encrypt_cfb8<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    cfb8(cipher, s, iv, true)
+}
+
+/// Decrypts a text in CFB mode with an 8-bit feedback segment.
+pub fn decrypt_cfb8<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    cfb8(cipher, s, iv, false)
+}
+
+/// Encrypts a text in OFB mode. Can also be used for decryption.
+pub fn encrypt_ofb<C: BlockCipher>(cipher: &C, s: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    check_iv(cipher, iv)?;
+    let mut res = vec![];
+    let mut keystream = iv.to_vec();
+    for block in s.chunks(cipher.block_size()) {
+        cipher.encrypt_block(&mut keystream);
+        res.extend(block.iter().zip(&keystream).map(|(&x, &y)| x ^ y));
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        ciphers::{encrypt_aes_128_cbc, encrypt_aes_128_ecb, Aes128, Aes192, Aes256},
+        convert::{from_hex, to_hex},
+    };
+
+    use super::*;
+
+    #[test]
+    fn modes_roundtrip_test() {
+        let key = from_hex("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+            .unwrap();
+        let plain = b"Burning 'em, if you ain't quick and nimble";
+        let iv = [7u8; 16];
+        let aes = Aes256::new(&key).unwrap();
+        assert_eq!(decrypt_ecb(&aes, &encrypt_ecb(&aes, plain)).unwrap(), plain);
+        let cipher = encrypt_cbc(&aes, plain, &iv).unwrap();
+        assert_eq!(decrypt_cbc(&aes, &cipher, &iv).unwrap(), plain);
+        let aes = Aes192::new(&key[..24]).unwrap();
+        let cipher = encrypt_ctr(&aes, plain, &iv[..8]).unwrap();
+        assert_eq!(encrypt_ctr(&aes, &cipher, &iv[..8]).unwrap(), plain);
+    }
+
+    #[test]
+    fn ctr_layout_test() {
+        // RFC 3686, test vector #2
+        let aes = Aes128::new(&from_hex("7E24067817FAE0D743D6CE1F32539163").unwrap()).unwrap();
+        let nonce = from_hex("006CB6DBC0543B59DA48D90B").unwrap();
+        let ctr = CTR::with_layout(&aes, &nonce, CounterLayout::RFC_3686).unwrap();
+        let plain: Vec<u8> = (0..32).collect();
+        let cipher: Vec<u8> = plain
+            .chunks(16)
+            .zip(ctr)
+            .flat_map(|(p, k)| fixed_xor(p, &k))
+            .collect();
+        assert_eq!(
+            to_hex(&cipher),
+            "5104A106168A72D9790D41EE8EDAD388EB2E1EFC46DA57C8FCE630DF9141BE28"
+        );
+
+        // The keystream of GCM with a 96-bit nonce
+        let gcm = crate::ciphers::Gcm::new(&aes);
+        let (expected, _) = gcm.encrypt(&nonce, &plain, &[]).unwrap();
+        let mut ctr = CTR::with_layout(&aes, &nonce, CounterLayout::GCM).unwrap();
+        ctr.seek_block(1);
+        let second = ctr.next().unwrap();
+        assert_eq!(fixed_xor(&plain[16..], &second), &expected[16..]);
+
+        // The counter wraps around without touching the nonce.
+        let layout = CounterLayout {
+            nonce_len: 15,
+            endian: Endian::Big,
+            initial: 255,
+        };
+        let mut ctr = CTR::with_layout(&aes, &[1; 15], layout).unwrap();
+        let zero = CounterLayout {
+            initial: 0,
+            ..layout
+        };
+        let mut zero = CTR::with_layout(&aes, &[1; 15], zero).unwrap();
+        assert_eq!(ctr.nth(1), zero.next());
+        assert!(matches!(
+            CTR::with_layout(&aes, &[1; 16], layout),
+            Err(Error::BadIvLength(16))
+        ));
+    }
+
+    #[test]
+    fn mode_objects_test() {
+        let key = b"YELLOW SUBMARINE";
+        let ecb = Ecb::new(Aes128::new(key).unwrap());
+        let cbc = Cbc::new(Aes128::new(key).unwrap());
+        for len in 0..40 {
+            let plain = vec![b'x'; len];
+            let iv = [len as u8; 16];
+            let cipher = ecb.encrypt(&plain);
+            assert_eq!(cipher, encrypt_aes_128_ecb(&plain, key).unwrap());
+            assert_eq!(ecb.decrypt(&cipher).unwrap(), plain);
+            let cipher = cbc.encrypt(&plain, &iv).unwrap();
+            assert_eq!(cipher, encrypt_aes_128_cbc(&plain, key, &iv).unwrap());
+            assert_eq!(cbc.decrypt(&cipher, &iv).unwrap(), plain);
+        }
+    }
+
+    #[test]
+    fn cbc_aes_256_test() {
+        // NIST SP 800-38A, F.2.5
+        let key = from_hex("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")
+            .unwrap();
+        let iv = from_hex("000102030405060708090a0b0c0d0e0f").unwrap();
+        let plain = from_hex("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51")
+            .unwrap();
+        let cipher = encrypt_cbc(&Aes256::new(&key).unwrap(), &plain, &iv).unwrap();
+        assert_eq!(
+            to_hex(&cipher[..32]),
+            "F58C4C04D6E5F1BA779EABFB5F7BFBD69CFC4E967EDB808D679F777BC6702C7D"
+        );
+    }
+
+    // NIST SP 800-38A, Appendix F, AES-128 examples.
+    const SP800_38A_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const SP800_38A_IV: &str = "000102030405060708090a0b0c0d0e0f";
+    const SP800_38A_PLAIN: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                                   30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+    #[test]
+    fn cfb_ofb_test() {
+        let aes = Aes128::new(&from_hex(SP800_38A_KEY).unwrap()).unwrap();
+        let iv = from_hex(SP800_38A_IV).unwrap();
+        let plain = from_hex(SP800_38A_PLAIN).unwrap();
+
+        // F.3.13
+        let cipher = encrypt_cfb(&aes, &plain, &iv).unwrap();
+        assert_eq!(
+            to_hex(&cipher),
+            "3B3FD92EB72DAD20333449F8E83CFB4AC8A64537A0B3A93FCDE3CDAD9F1CE58B\
+             26751F67A3CBB140B1808CF187A4F4DFC04B05357C5D1C0EEAC4C66F9FF7F2E6"
+        );
+        assert_eq!(decrypt_cfb(&aes, &cipher, &iv).unwrap(), plain);
+        // A partial final segment
+        assert_eq!(encrypt_cfb(&aes, &plain[..20], &iv).unwrap(), &cipher[..20]);
+        assert_eq!(decrypt_cfb(&aes, &cipher[..20], &iv).unwrap(), &plain[..20]);
+
+        // F.3.7
+        let cipher = encrypt_cfb8(&aes, &plain[..18], &iv).unwrap();
+        assert_eq!(to_hex(&cipher), "3B79424C9C0DD436BACE9E0ED4586A4F32B9");
+        assert_eq!(decrypt_cfb8(&aes, &cipher, &iv).unwrap(), &plain[..18]);
+
+        // F.4.1
+        let cipher = encrypt_ofb(&aes, &plain, &iv).unwrap();
+        assert_eq!(
+            to_hex(&cipher),
+            "3B3FD92EB72DAD20333449F8E83CFB4A7789508D16918F03F53C52DAC54ED825\
+             9740051E9C5FECF64344F7A82260EDCC304C6528F659C77866A510D9C1D6AE5E"
+        );
+        assert_eq!(encrypt_ofb(&aes, &cipher, &iv).unwrap(), plain);
+    }
+
+    #[test]
+    fn pcbc_test() {
+        let aes = Aes128::new(b"YELLOW SUBMARINE").unwrap();
+        let iv = [3u8; 16];
+        let plain = b"Burning 'em, if you ain't quick and nimble";
+        let cipher = encrypt_pcbc(&aes, plain, &iv).unwrap();
+        // The first block is the same as in CBC, the rest differ.
+        let cbc = encrypt_cbc(&aes, plain, &iv).unwrap();
+        assert_eq!(cipher[..16], cbc[..16]);
+        assert_ne!(cipher[16..], cbc[16..]);
+        assert_eq!(decrypt_pcbc(&aes, &cipher, &iv).unwrap(), plain);
+    }
+
+    #[test]
+    fn cts_test() {
+        // RFC 3962, Appendix B
+        let aes = Aes128::new(b"chicken teriyaki").unwrap();
+        let iv = [0u8; 16];
+        let vectors = [
+            (
+                &b"I would like the "[..],
+                "C6353568F2BF8CB4D8A580362DA7FF7F97",
+            ),
+            (
+                b"I would like the General Gau's ",
+                "FC00783E0EFDB2C1D445D4C8EFF7ED2297687268D6ECCCC0C07B25E25ECFE5",
+            ),
+            (
+                b"I would like the General Gau's C",
+                "39312523A78662D5BE7FCBCC98EBF5A897687268D6ECCCC0C07B25E25ECFE584",
+            ),
+        ];
+        for (plain, expected) in vectors {
+            let cipher = encrypt_cbc_cts(&aes, plain, &iv, Cts::Cs3).unwrap();
+            assert_eq!(to_hex(&cipher), expected);
+            assert_eq!(decrypt_cbc_cts(&aes, &cipher, &iv, Cts::Cs3).unwrap(), plain);
+        }
+
+        let plain = b"I would like the General Gau's Chicken, please, and wonton soup.";
+        for len in 16..plain.len() {
+            let plain = &plain[..len];
+            let cs1 = encrypt_cbc_cts(&aes, plain, &iv, Cts::Cs1).unwrap();
+            let cs2 = encrypt_cbc_cts(&aes, plain, &iv, Cts::Cs2).unwrap();
+            let cs3 = encrypt_cbc_cts(&aes, plain, &iv, Cts::Cs3).unwrap();
+            assert_eq!(cs1.len(), len);
+            if len % 16 == 0 {
+                // CS1 and CS2 are plain CBC without padding
+                let cbc = encrypt_cbc(&aes, plain, &iv).unwrap();
+                assert_eq!(cs1, cbc[..len]);
+                assert_eq!(cs2, cs1);
+            } else {
+                assert_eq!(cs2, cs3);
+            }
+            for (cipher, variant) in [(cs1, Cts::Cs1), (cs2, Cts::Cs2), (cs3, Cts::Cs3)] {
+                assert_eq!(decrypt_cbc_cts(&aes, &cipher, &iv, variant).unwrap(), plain);
+            }
+        }
+        assert!(matches!(
+            encrypt_cbc_cts(&aes, &plain[..15], &iv, Cts::Cs1),
+            Err(Error::BadInputLength(15))
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::{Mutex, PoisonError};
+
+use openssl::symm::{Cipher, Crypter, Mode};
+
+use crate::{
+    ciphers::block::BlockCipher,
+    error::{Error, Result},
+};
+
+/// Creates an ECB crypter without padding, so that each update processes whole blocks.
+fn new_crypter(cipher: Cipher, mode: Mode, key: &[u8]) -> Result<Crypter> {
+    let mut crypter = Crypter::new(cipher, mode, key, None)?;
+    crypter.pad(false);
+    Ok(crypter)
+}
+
+/// Keyed OpenSSL contexts for one direction. A context is needed mutably for each block, so
+/// every thread takes one from the pool for the duration of a block, and a new one is only set
+/// up when all are in use.
+struct CrypterPool {
+    cipher: Cipher,
+    mode: Mode,
+    key: Vec<u8>,
+    free: Mutex<Vec<Crypter>>,
+}
+
+impl CrypterPool {
+    fn new(cipher: Cipher, mode: Mode, key: &[u8]) -> Result<Self> {
+        let crypter = new_crypter(cipher, mode, key)?;
+        Ok(Self {
+            cipher,
+            mode,
+            key: key.to_vec(),
+            free: Mutex::new(vec![crypter]),
+        })
+    }
+
+    /// Runs a single block through one of the contexts.
+    fn process(&self, block: &mut [u8]) {
+        assert_eq!(block.len(), 16, "AES block must be 16 bytes");
+        // The lock is never held while a context is in use, so a poisoned pool is still whole.
+        let free = || self.free.lock().unwrap_or_else(PoisonError::into_inner);
+        let crypter = free().pop();
+        let mut crypter =
+            crypter.unwrap_or_else(|| new_crypter(self.cipher, self.mode, &self.key).unwrap());
+        let mut out = [0u8; 32];
+        crypter.update(block, &mut out).unwrap();
+        block.copy_from_slice(&out[..16]);
+        free().push(crypter);
+    }
+}
+
+macro_rules! openssl_aes {
+    ($name:ident, $key_len:expr, $cipher:expr) => {
+        #[doc = concat!("AES with a ", stringify!($key_len), "-byte key.")]
+        /// The OpenSSL contexts are keyed once, and reused for every block.
+        pub struct $name {
+            encrypter: CrypterPool,
+            decrypter: CrypterPool,
+        }
+
+        impl $name {
+            pub fn new(key: &[u8]) -> Result<Self> {
+                if key.len() != $key_len {
+                    return Err(Error::BadKeyLength(key.len()));
+                }
+                Ok(Self {
+                    encrypter: CrypterPool::new($cipher, Mode::Encrypt, key)?,
+                    decrypter: CrypterPool::new($cipher, Mode::Decrypt, key)?,
+                })
+            }
+        }
+
+        impl BlockCipher for $name {
+            fn block_size(&self) -> usize {
+                16
+            }
+
+            fn encrypt_block(&self, block: &mut [u8]) {
+                self.encrypter.process(block);
+            }
+
+            fn decrypt_block(&self, block: &mut [u8]) {
+                self.decrypter.process(block);
+            }
+        }
+    };
+}
+
+openssl_aes!(Aes128, 16, Cipher::aes_128_ecb());
+openssl_aes!(Aes192, 24, Cipher::aes_192_ecb());
+openssl_aes!(Aes256, 32, Cipher::aes_256_ecb());
+
+#[cfg(test)]
+pub mod tests {
+    use crate::convert::{from_hex, to_hex};
+
+    use super::*;
+
+    fn check<C: BlockCipher>(cipher: &C, plain: &str, expected: &str) {
+        let mut block = from_hex(plain).unwrap();
+        cipher.encrypt_block(&mut block);
+        assert_eq!(to_hex(&block), expected);
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block, from_hex(plain).unwrap());
+    }
+
+    #[test]
+    fn fips_197_test() {
+        // FIPS-197, Appendix C
+        let plain = "00112233445566778899aabbccddeeff";
+        let key = from_hex("000102030405060708090a0b0c0d0e0f1011121314151617").unwrap();
+        let key_long = from_hex(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        )
+        .unwrap();
+        check(
+            &Aes128::new(&key[..16]).unwrap(),
+            plain,
+            "69C4E0D86A7B0430D8CDB78070B4C55A",
+        );
+        check(
+            &Aes192::new(&key).unwrap(),
+            plain,
+            "DDA97CA4864CDFE06EAF70A0EC0D7191",
+        );
+        check(
+            &Aes256::new(&key_long).unwrap(),
+            plain,
+            "8EA2B7CA516745BFEAFC49904B496089",
+        );
+        assert!(matches!(Aes256::new(&key), Err(Error::BadKeyLength(24))));
+    }
+
+    #[test]
+    fn context_reuse_test() {
+        let aes = Aes128::new(b"YELLOW SUBMARINE").unwrap();
+        let mut expected = *b"sixteen byte msg";
+        aes.encrypt_block(&mut expected);
+        for _ in 0..100 {
+            let mut block = *b"sixteen byte msg";
+            aes.encrypt_block(&mut block);
+            assert_eq!(block, expected);
+        }
+        assert_eq!(aes.encrypter.free.lock().unwrap().len(), 1);
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..100 {
+                        let mut block = expected;
+                        aes.decrypt_block(&mut block);
+                        assert_eq!(&block, b"sixteen byte msg");
+                    }
+                });
+            }
+        });
+        let contexts = aes.decrypter.free.lock().unwrap().len();
+        assert!((1..=4).contains(&contexts));
+    }
+}
@@ -0,0 +1,407 @@
+use std::io::{self, Read, Write};
+
+use crate::{
+    ciphers::{
+        block::BlockCipher,
+        fixed_xor,
+        modes::{CounterLayout, CTR},
+    },
+    error::{Error, Result},
+    mersenne::MT19937,
+    util::{pad, unpad_in_place},
+};
+
+/// An incremental encryptor or decryptor.
+/// The input can be fed in pieces of any size; the concatenated outputs of all the update calls
+/// and the final call are the same as processing the whole input at once.
+pub trait Transform {
+    /// Processes more input, returning the output that is ready so far.
+    fn update(&mut self, input: &[u8]) -> Result<Vec<u8>>;
+
+    /// Marks the end of the input, returning the remaining output.
+    /// The transform should not be used afterwards.
+    fn finalize(&mut self) -> Result<Vec<u8>>;
+}
+
+/// Incremental CBC encryption, with PKCS#7 padding applied in finalize.
+pub struct CbcEncryptor<C: BlockCipher> {
+    cipher: C,
+    prev_block: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl<C: BlockCipher> CbcEncryptor<C> {
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self> {
+        if iv.len() != cipher.block_size() {
+            return Err(Error::BadIvLength(iv.len()));
+        }
+        Ok(Self {
+            cipher,
+            prev_block: iv.to_vec(),
+            buf: vec![],
+        })
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &[u8]) -> Vec<u8> {
+        let mut res = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            let mut block = fixed_xor(block, &self.prev_block);
+            self.cipher.encrypt_block(&mut block);
+            res.extend_from_slice(&block);
+            self.prev_block = block;
+        }
+        res
+    }
+}
+
+impl<C: BlockCipher> Transform for CbcEncryptor<C> {
+    fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        self.buf.extend_from_slice(input);
+        let whole = self.buf.len() / self.cipher.block_size() * self.cipher.block_size();
+        let blocks: Vec<u8> = self.buf.drain(..whole).collect();
+        Ok(self.encrypt_blocks(&blocks))
+    }
+
+    fn finalize(&mut self) -> Result<Vec<u8>> {
+        let last = pad(&std::mem::take(&mut self.buf), self.cipher.block_size());
+        Ok(self.encrypt_blocks(&last))
+    }
+}
+
+/// Incremental CBC decryption. The last block is held back until finalize, where the padding
+/// is checked and removed.
+pub struct CbcDecryptor<C: BlockCipher> {
+    cipher: C,
+    prev_block: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl<C: BlockCipher> CbcDecryptor<C> {
+    pub fn new(cipher: C, iv: &[u8]) -> Result<Self> {
+        if iv.len() != cipher.block_size() {
+            return Err(Error::BadIvLength(iv.len()));
+        }
+        Ok(Self {
+            cipher,
+            prev_block: iv.to_vec(),
+            buf: vec![],
+        })
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &[u8]) -> Vec<u8> {
+        let mut res = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            let mut plain = block.to_vec();
+            self.cipher.decrypt_block(&mut plain);
+            res.extend_from_slice(&fixed_xor(&plain, &self.prev_block));
+            self.prev_block = block.to_vec();
+        }
+        res
+    }
+}
+
+impl<C: BlockCipher> Transform for CbcDecryptor<C> {
+    fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        self.buf.extend_from_slice(input);
+        let block_size = self.cipher.block_size();
+        if self.buf.len() <= block_size {
+            return Ok(vec![]);
+        }
+        // Keep at least one byte back, so the last complete block stays in the buffer.
+        let ready = (self.buf.len() - 1) / block_size * block_size;
+        let blocks: Vec<u8> = self.buf.drain(..ready).collect();
+        Ok(self.decrypt_blocks(&blocks))
+    }
+
+    fn finalize(&mut self) -> Result<Vec<u8>> {
+        let last = std::mem::take(&mut self.buf);
+        if last.len() != self.cipher.block_size() {
+            return Err(Error::BadInputLength(last.len()));
+        }
+        let mut res = self.decrypt_blocks(&last);
+        unpad_in_place(&mut res)?;
+        Ok(res)
+    }
+}
+
+/// Xors the input with a keystream that is produced a chunk at a time.
+struct Keystream<I: Iterator<Item = Vec<u8>>> {
+    chunks: I,
+    current: Vec<u8>,
+    pos: usize,
+}
+
+impl<I: Iterator<Item = Vec<u8>>> Keystream<I> {
+    fn new(chunks: I) -> Self {
+        Self {
+            chunks,
+            current: vec![],
+            pos: 0,
+        }
+    }
+
+    fn apply(&mut self, input: &[u8]) -> Vec<u8> {
+        let mut res = Vec::with_capacity(input.len());
+        for &c in input {
+            if self.pos == self.current.len() {
+                self.current = self.chunks.next().expect("keystream is infinite");
+                self.pos = 0;
+            }
+            res.push(c ^ self.current[self.pos]);
+            self.pos += 1;
+        }
+        res
+    }
+
+    /// Restarts from the next chunk, skipping its first bytes.
+    fn restart(&mut self, skip: usize) {
+        self.current = vec![];
+        self.pos = 0;
+        if skip > 0 {
+            self.current = self.chunks.next().expect("keystream is infinite");
+            self.pos = skip;
+        }
+    }
+}
+
+/// Incremental CTR mode. Encryption and decryption are the same operation.
+/// The position in the keystream can be moved with seek, for random access.
+pub struct CtrStream<C: BlockCipher> {
+    keystream: Keystream<CTR<C>>,
+    block_size: usize,
+}
+
+impl<C: BlockCipher> CtrStream<C> {
+    /// Uses the counter layout of the challenges, see CTR::new.
+    pub fn new(cipher: C, nonce: &[u8]) -> Result<Self> {
+        let layout = CounterLayout::half_block(cipher.block_size());
+        Self::with_layout(cipher, nonce, layout)
+    }
+
+    pub fn with_layout(cipher: C, nonce: &[u8], layout: CounterLayout) -> Result<Self> {
+        let block_size = cipher.block_size();
+        Ok(Self {
+            keystream: Keystream::new(CTR::with_layout(cipher, nonce, layout)?),
+            block_size,
+        })
+    }
+
+    /// Moves to the given byte offset of the keystream, without generating the preceding blocks.
+    pub fn seek(&mut self, offset: u64) {
+        let block_size = self.block_size as u64;
+        self.keystream.chunks.seek_block(offset / block_size);
+        self.keystream.restart((offset % block_size) as usize);
+    }
+}
+
+impl<C: BlockCipher> Transform for CtrStream<C> {
+    fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.keystream.apply(input))
+    }
+
+    fn finalize(&mut self) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+}
+
+/// MT19937 outputs as little-endian bytes.
+type MtChunks = std::iter::Map<MT19937, fn(u32) -> Vec<u8>>;
+
+/// Incremental version of mt19937_stream_cipher. Encryption and decryption are the same operation.
+pub struct Mt19937Stream {
+    keystream: Keystream<MtChunks>,
+}
+
+impl Mt19937Stream {
+    pub fn new(secret_key: u32) -> Self {
+        let to_bytes: fn(u32) -> Vec<u8> = |k| k.to_le_bytes().to_vec();
+        Self {
+            keystream: Keystream::new(MT19937::new(secret_key).map(to_bytes)),
+        }
+    }
+}
+
+impl Transform for Mt19937Stream {
+    fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.keystream.apply(input))
+    }
+
+    fn finalize(&mut self) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+}
+
+fn to_io_error(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Writes the transformed data to the inner writer.
+/// Call finish at the end, to write out the final block.
+pub struct TransformWriter<T: Transform, W: Write> {
+    transform: T,
+    inner: W,
+}
+
+impl<T: Transform, W: Write> TransformWriter<T, W> {
+    pub fn new(transform: T, inner: W) -> Self {
+        Self { transform, inner }
+    }
+
+    /// Finalizes the transform, and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let out = self.transform.finalize().map_err(to_io_error)?;
+        self.inner.write_all(&out)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<T: Transform, W: Write> Write for TransformWriter<T, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let out = self.transform.update(buf).map_err(to_io_error)?;
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads data from the inner reader, and returns it transformed.
+/// The transform is finalized when the inner reader runs out.
+pub struct TransformReader<T: Transform, R: Read> {
+    transform: T,
+    inner: R,
+    out: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+const READ_CHUNK: usize = 8192;
+
+impl<T: Transform, R: Read> TransformReader<T, R> {
+    pub fn new(transform: T, inner: R) -> Self {
+        Self {
+            transform,
+            inner,
+            out: vec![],
+            pos: 0,
+            done: false,
+        }
+    }
+}
+
+impl<T: Transform, R: Read> Read for TransformReader<T, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() {
+            if self.done {
+                return Ok(0);
+            }
+            let mut chunk = [0u8; READ_CHUNK];
+            let n = self.inner.read(&mut chunk)?;
+            self.out = if n == 0 {
+                self.done = true;
+                self.transform.finalize()
+            } else {
+                self.transform.update(&chunk[..n])
+            }
+            .map_err(to_io_error)?;
+            self.pos = 0;
+        }
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::ciphers::{encrypt_cbc, encrypt_ctr, mt19937_stream_cipher, Aes128};
+
+    use super::*;
+
+    /// Feeds the input in pieces of increasing size.
+    fn run<T: Transform>(mut transform: T, input: &[u8]) -> Result<Vec<u8>> {
+        let mut res = vec![];
+        let mut rest = input;
+        let mut size = 0;
+        while !rest.is_empty() {
+            size = (size + 1).min(rest.len());
+            let (piece, tail) = rest.split_at(size);
+            res.extend(transform.update(piece)?);
+            rest = tail;
+        }
+        res.extend(transform.finalize()?);
+        Ok(res)
+    }
+
+    #[test]
+    fn incremental_test() {
+        let key = b"YELLOW SUBMARINE";
+        let iv = [5u8; 16];
+        let plain = include_str!("../../data/funky_music.txt").as_bytes();
+        for len in [0, 1, 15, 16, 17, 100, plain.len()] {
+            let plain = &plain[..len];
+            let aes = Aes128::new(key).unwrap();
+            let cipher = encrypt_cbc(&aes, plain, &iv).unwrap();
+            let enc = CbcEncryptor::new(&aes, &iv).unwrap();
+            assert_eq!(run(enc, plain).unwrap(), cipher);
+            let dec = CbcDecryptor::new(&aes, &iv).unwrap();
+            assert_eq!(run(dec, &cipher).unwrap(), plain);
+
+            let cipher = encrypt_ctr(&aes, plain, &iv[..8]).unwrap();
+            let ctr = CtrStream::new(&aes, &iv[..8]).unwrap();
+            assert_eq!(run(ctr, plain).unwrap(), cipher);
+
+            let cipher = mt19937_stream_cipher(plain, 1234);
+            assert_eq!(run(Mt19937Stream::new(1234), plain).unwrap(), cipher);
+        }
+        let aes = Aes128::new(key).unwrap();
+        let dec = CbcDecryptor::new(&aes, &iv).unwrap();
+        assert!(matches!(run(dec, &[0; 20]), Err(Error::BadInputLength(4))));
+    }
+
+    #[test]
+    fn ctr_seek_test() {
+        let aes = Aes128::new(b"YELLOW SUBMARINE").unwrap();
+        let plain = include_str!("../../data/funky_music.txt").as_bytes();
+        let cipher = encrypt_ctr(&aes, plain, &[3; 8]).unwrap();
+        let mut ctr = CtrStream::new(&aes, &[3; 8]).unwrap();
+        for offset in [1000, 0, 15, 16, 17, 333] {
+            ctr.seek(offset as u64);
+            let part = ctr.update(&plain[offset..offset + 40]).unwrap();
+            assert_eq!(part, &cipher[offset..offset + 40]);
+        }
+    }
+
+    #[test]
+    fn io_adapters_test() {
+        let key = b"YELLOW SUBMARINE";
+        let iv = [9u8; 16];
+        let plain = include_str!("../../data/funky_music.txt").as_bytes();
+
+        let enc = CbcEncryptor::new(Aes128::new(key).unwrap(), &iv).unwrap();
+        let mut writer = TransformWriter::new(enc, vec![]);
+        for chunk in plain.chunks(100) {
+            writer.write_all(chunk).unwrap();
+        }
+        let cipher = writer.finish().unwrap();
+        assert_eq!(
+            cipher,
+            encrypt_cbc(&Aes128::new(key).unwrap(), plain, &iv).unwrap()
+        );
+
+        let dec = CbcDecryptor::new(Aes128::new(key).unwrap(), &iv).unwrap();
+        let mut reader = TransformReader::new(dec, &cipher[..]);
+        let mut decrypted = vec![];
+        reader.read_to_end(&mut decrypted).unwrap();
+        assert_eq!(decrypted, plain);
+
+        let dec = CbcDecryptor::new(Aes128::new(key).unwrap(), &iv).unwrap();
+        let mut reader = TransformReader::new(dec, &cipher[..cipher.len() - 1]);
+        let err = reader.read_to_end(&mut vec![]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,16 +1,18 @@
+use crate::error::{Error, Result};
+
 /// Converts a base 16 character to a number
-fn hex_to_num(c: u8) -> u8 {
+fn hex_to_num(c: u8) -> Result<u8> {
     if c.is_ascii_digit() {
-        return c - b'0';
+        return Ok(c - b'0');
     } else if (b'A'..=b'F').contains(&c) {
-        return c - b'A' + 10;
+        return Ok(c - b'A' + 10);
     } else if (b'a'..=b'f').contains(&c) {
-        return c - b'a' + 10;
+        return Ok(c - b'a' + 10);
     }
-    unreachable!()
+    Err(Error::InvalidHex)
 }
 
-//// Converts a number in the range [0, 16) to a hex character
+/// Converts a number in the range [0, 16) to a hex character
 fn num_to_hex(n: u8) -> char {
     if (0..10).contains(&n) {
         (b'0' + n) as char
@@ -23,13 +25,16 @@ fn byte_to_hex(n: u8) -> [char; 2] {
     [num_to_hex(n >> 4), num_to_hex(n & 0xF)]
 }
 
-pub fn from_hex(hex: &str) -> Vec<u8> {
-    assert!(hex.len() % 2 == 0, "Hex string must have even length");
+/// Decodes a hex string. Fails on odd length or non-hex characters.
+pub fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(Error::InvalidHex);
+    }
     let mut res = Vec::with_capacity(hex.len() / 2);
     for w in hex.as_bytes().chunks_exact(2) {
-        res.push((hex_to_num(w[0]) << 4) + hex_to_num(w[1]));
+        res.push((hex_to_num(w[0])? << 4) + hex_to_num(w[1])?);
     }
-    res
+    Ok(res)
 }
 
 pub fn to_hex(bstr: &[u8]) -> String {
@@ -39,7 +44,7 @@ pub fn to_hex(bstr: &[u8]) -> String {
         .collect()
 }
 
-//// Converts a number in the range [0, 64) to a b64 character
+/// Converts a number in the range [0, 64) to a b64 character
 fn num_to_base64(n: u8) -> char {
     match n {
         _ if n < 26 => (b'A' + n) as char,
@@ -51,21 +56,21 @@ fn num_to_base64(n: u8) -> char {
     }
 }
 
-//// Converts a b64 character into the corresponding number
-fn base64_to_num(c: u8) -> u8 {
+/// Converts a b64 character into the corresponding number
+fn base64_to_num(c: u8) -> Result<u8> {
     match c {
-        _ if c.is_ascii_uppercase() => c - b'A',
-        _ if c.is_ascii_lowercase() => 26 + (c - b'a'),
-        _ if c.is_ascii_digit() => 52 + (c - b'0'),
-        b'+' => 62,
-        b'/' => 63,
-        _ => unimplemented!("Invalid base64 character: {}", c as char),
+        _ if c.is_ascii_uppercase() => Ok(c - b'A'),
+        _ if c.is_ascii_lowercase() => Ok(26 + (c - b'a')),
+        _ if c.is_ascii_digit() => Ok(52 + (c - b'0')),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(Error::InvalidBase64),
     }
 }
 
 /// Takes a base64 ASCII string with optional padding and decodes into a bytestring
 /// Skips whitespace
-pub fn from_base64(b64: &[u8]) -> Vec<u8> {
+pub fn from_base64(b64: &[u8]) -> Result<Vec<u8>> {
     let mut buf;
     let mut iter = b64.iter().filter(|c| !c.is_ascii_whitespace());
     let mut res = vec![];
@@ -77,7 +82,7 @@ pub fn from_base64(b64: &[u8]) -> Vec<u8> {
                 if c == b'=' {
                     break;
                 }
-                buf[k] = base64_to_num(c);
+                buf[k] = base64_to_num(c)?;
                 k += 1;
             } else {
                 break;
@@ -86,7 +91,10 @@ pub fn from_base64(b64: &[u8]) -> Vec<u8> {
         if k == 0 {
             break;
         }
-        assert!(k >= 2, "Base64 string cannot have a chunk of size 1");
+        // A base64 string cannot have a chunk of size 1
+        if k < 2 {
+            return Err(Error::InvalidBase64);
+        }
         res.push((buf[0] << 2) | (buf[1] >> 4));
         if k >= 3 {
             res.push(((buf[1] & 0x0F) << 4) | (buf[2] >> 2));
@@ -95,7 +103,7 @@ pub fn from_base64(b64: &[u8]) -> Vec<u8> {
             res.push(((buf[2] & 0x03) << 6) | buf[3]);
         }
     }
-    res
+    Ok(res)
 }
 
 /// Takes a bytestring and encodes as base64. Output is padded and always has length divisible by 4.
@@ -128,20 +136,28 @@ pub mod tests {
     #[test]
     fn base64_small_test() {
         assert_eq!(to_base64(b"Man"), "TWFu");
-        assert_eq!(from_base64(b"TWFu"), b"Man");
+        assert_eq!(from_base64(b"TWFu").unwrap(), b"Man");
         assert_eq!(to_base64(b"Ma"), "TWE=");
-        assert_eq!(from_base64(b"TWE="), b"Ma");
-        assert_eq!(from_base64(b"TWE"), b"Ma");
+        assert_eq!(from_base64(b"TWE=").unwrap(), b"Ma");
+        assert_eq!(from_base64(b"TWE").unwrap(), b"Ma");
         assert_eq!(to_base64(b"M"), "TQ==");
-        assert_eq!(from_base64(b"TQ=="), b"M");
-        assert_eq!(from_base64(b"TQ"), b"M");
+        assert_eq!(from_base64(b"TQ==").unwrap(), b"M");
+        assert_eq!(from_base64(b"TQ").unwrap(), b"M");
     }
 
     #[test]
     fn base64_medium_test() {
-        let s = from_hex("49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d");
+        let s = from_hex("49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d").unwrap();
         let b64 = "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t";
         assert_eq!(to_base64(&s), b64);
-        assert_eq!(&from_base64(b64.as_bytes()), &s);
+        assert_eq!(&from_base64(b64.as_bytes()).unwrap(), &s);
+    }
+
+    #[test]
+    fn invalid_input_test() {
+        assert!(matches!(from_hex("abc"), Err(Error::InvalidHex)));
+        assert!(matches!(from_hex("zz"), Err(Error::InvalidHex)));
+        assert!(matches!(from_base64(b"TW!u"), Err(Error::InvalidBase64)));
+        assert!(matches!(from_base64(b"TWFuT"), Err(Error::InvalidBase64)));
     }
 }
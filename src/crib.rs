@@ -0,0 +1,218 @@
+use crate::{
+    error::{Error, Result},
+    freq::Scorer,
+};
+
+/// Crib dragging over texts encrypted with the same keystream (a many-time pad, or CTR with a
+/// fixed nonce).
+/// Guessing a crib as the plaintext of one text at some offset gives the keystream there, and
+/// with it fragments of all the other texts. Guesses that look right can be locked in.
+pub struct CribDragger {
+    ciphers: Vec<Vec<u8>>,
+    keystream: Vec<Option<u8>>,
+}
+
+/// Renders a byte for display, with '.' for anything unprintable.
+pub fn printable(c: u8) -> char {
+    if c.is_ascii_graphic() || c == b' ' {
+        c as char
+    } else {
+        '.'
+    }
+}
+
+impl CribDragger {
+    pub fn new(ciphers: Vec<Vec<u8>>) -> Self {
+        let max_len = ciphers.iter().map(|c| c.len()).max().unwrap_or(0);
+        Self {
+            ciphers,
+            keystream: vec![None; max_len],
+        }
+    }
+
+    pub fn ciphers(&self) -> &[Vec<u8>] {
+        &self.ciphers
+    }
+
+    /// The keystream bytes locked in so far.
+    pub fn keystream(&self) -> &[Option<u8>] {
+        &self.keystream
+    }
+
+    fn check_range(&self, index: usize, offset: usize, len: usize) -> Result<()> {
+        let text_len = self.ciphers.get(index).map_or(0, |c| c.len());
+        match offset.checked_add(len) {
+            Some(end) if end <= text_len => Ok(()),
+            Some(end) => Err(Error::BadInputLength(end)),
+            None => Err(Error::BadInputLength(len)),
+        }
+    }
+
+    /// The keystream implied by the crib being the plaintext of text index at offset.
+    fn crib_keystream(&self, index: usize, offset: usize, crib: &[u8]) -> Result<Vec<u8>> {
+        self.check_range(index, offset, crib.len())?;
+        let cipher = &self.ciphers[index][offset..offset + crib.len()];
+        Ok(cipher.iter().zip(crib).map(|(&c, &p)| c ^ p).collect())
+    }
+
+    /// Takes the crib as the plaintext of text index at offset, and returns the resulting
+    /// fragment of every text, cut short for texts that end earlier.
+    pub fn try_crib(&self, index: usize, offset: usize, crib: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let keystream = self.crib_keystream(index, offset, crib)?;
+        Ok(self
+            .ciphers
+            .iter()
+            .map(|c| {
+                c.get(offset..c.len().min(offset + crib.len()))
+                    .unwrap_or(&[])
+                    .iter()
+                    .zip(&keystream)
+                    .map(|(&x, &k)| x ^ k)
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Slides the crib across text index, scoring each offset by the fragments it produces in
+    /// the other texts. Returns the offsets with their scores, best first.
+    pub fn drag<S: Scorer + ?Sized>(
+        &self,
+        index: usize,
+        crib: &[u8],
+        scorer: &S,
+    ) -> Vec<(usize, f64)> {
+        let len = self.ciphers.get(index).map_or(0, |c| c.len());
+        let mut res = vec![];
+        for offset in 0..(len + 1).saturating_sub(crib.len()) {
+            let fragments = self.try_crib(index, offset, crib).unwrap();
+            let others: Vec<u8> = fragments
+                .into_iter()
+                .enumerate()
+                .filter(|&(i, _)| i != index)
+                .flat_map(|(_, f)| f)
+                .collect();
+            if !others.is_empty() {
+                res.push((offset, scorer.score(&others)));
+            }
+        }
+        res.sort_by(|a, b| a.1.total_cmp(&b.1));
+        res
+    }
+
+    /// Locks in the keystream given by the crib as the plaintext of text index at offset.
+    pub fn lock(&mut self, index: usize, offset: usize, crib: &[u8]) -> Result<()> {
+        let keystream = self.crib_keystream(index, offset, crib)?;
+        for (i, k) in keystream.into_iter().enumerate() {
+            self.keystream[offset + i] = Some(k);
+        }
+        Ok(())
+    }
+
+    /// Locks in the keystream bytes whose confidence is at least the threshold, as returned by
+    /// recover_fixed_nonce_keystream.
+    pub fn lock_confident(&mut self, recovered: &[(u8, f64)], threshold: f64) {
+        for (slot, &(k, confidence)) in self.keystream.iter_mut().zip(recovered) {
+            if confidence >= threshold {
+                *slot = Some(k);
+            }
+        }
+    }
+
+    /// Forgets the keystream bytes in the range.
+    pub fn unlock(&mut self, offset: usize, len: usize) {
+        let end = offset.saturating_add(len).min(self.keystream.len());
+        for slot in self.keystream.iter_mut().take(end).skip(offset) {
+            *slot = None;
+        }
+    }
+
+    /// The plaintexts decrypted so far, None where the keystream is unknown.
+    pub fn plaintexts(&self) -> Vec<Vec<Option<u8>>> {
+        self.ciphers
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .zip(&self.keystream)
+                    .map(|(&x, k)| k.map(|k| x ^ k))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The plaintexts decrypted so far, for display: '_' for unknown bytes, '.' for unprintable.
+    pub fn render(&self) -> Vec<String> {
+        self.plaintexts()
+            .into_iter()
+            .map(|p| p.into_iter().map(|c| c.map_or('_', printable)).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        ciphers::{encrypt_aes_128_ctr, recover_fixed_nonce_keystream},
+        freq::LogLikelihood,
+    };
+
+    use super::*;
+
+    fn dragger() -> CribDragger {
+        let ciphers = [
+            "I have met them at close of day",
+            "Coming with vivid faces",
+            "From counter or desk among grey",
+        ]
+        .iter()
+        .map(|p| encrypt_aes_128_ctr(p.as_bytes(), b"YELLOW SUBMARINE", &[0; 8]).unwrap())
+        .collect();
+        CribDragger::new(ciphers)
+    }
+
+    #[test]
+    fn drag_test() {
+        let mut dragger = dragger();
+        let fragments = dragger.try_crib(1, 7, b"with").unwrap();
+        assert_eq!(fragments, [b"met ", b"with", b"unte"]);
+        let ranking = dragger.drag(1, b" vivid ", &LogLikelihood::english());
+        assert_eq!(ranking[0].0, 11);
+        assert!(matches!(
+            dragger.try_crib(1, 20, b"faces"),
+            Err(Error::BadInputLength(25))
+        ));
+        assert!(matches!(
+            dragger.lock(1, usize::MAX, b"faces"),
+            Err(Error::BadInputLength(5))
+        ));
+
+        dragger.lock(0, 0, b"I have").unwrap();
+        dragger.lock(2, 26, b" grey").unwrap();
+        let rendered = dragger.render();
+        assert_eq!(rendered[0], "I have____________________f day");
+        assert_eq!(rendered[1], "Coming_________________");
+        dragger.unlock(2, 100);
+        assert_eq!(dragger.render()[2], "Fr_____________________________");
+        dragger.unlock(1, usize::MAX);
+        assert_eq!(dragger.render()[0], "I______________________________");
+    }
+
+    #[test]
+    fn short_texts_test() {
+        let dragger = dragger();
+        // Past the end of the second text, which is 23 bytes long.
+        let fragments = dragger.try_crib(0, 25, b"ab").unwrap();
+        assert_eq!(fragments[1], b"");
+        assert_eq!(fragments[2].len(), 2);
+        assert_eq!(dragger.drag(0, b" day", &LogLikelihood::english())[0].0, 27);
+    }
+
+    #[test]
+    fn lock_confident_test() {
+        let mut dragger = dragger();
+        let recovered = recover_fixed_nonce_keystream(dragger.ciphers(), &LogLikelihood::english());
+        dragger.lock_confident(&recovered, 2.0);
+        assert!(dragger.keystream().iter().all(|k| k.is_none()));
+        dragger.lock_confident(&recovered, 0.0);
+        assert!(dragger.keystream().iter().all(|k| k.is_some()));
+    }
+}
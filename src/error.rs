@@ -0,0 +1,83 @@
+use std::fmt;
+
+/// Errors returned when decoding or decrypting malformed input.
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid hex digit, or a hex string of odd length.
+    InvalidHex,
+    /// Invalid base64 character, or a chunk of a single character.
+    InvalidBase64,
+    /// Invalid %-code in a URL-encoded string.
+    InvalidUrlEncoding,
+    /// A cookie entry that is not of the form X=Y.
+    MalformedCookie,
+    /// PKCS#7 padding is missing or inconsistent.
+    BadPadding,
+    /// The key has an unsupported length (the length given).
+    BadKeyLength(usize),
+    /// The IV or nonce has the wrong length (the length given).
+    BadIvLength(usize),
+    /// The authentication tag does not match.
+    BadTag,
+    /// The input is not a whole number of blocks (the length given).
+    BadInputLength(usize),
+    /// A key derivation function cannot produce that many bytes (the length requested).
+    BadOutputLength(usize),
+    /// A key derivation function was asked for an unsupported number of iterations (the count
+    /// given).
+    BadIterationCount(u32),
+    /// A serialized n-gram model is truncated or malformed.
+    InvalidModel,
+    /// The observed outputs of a generator do not determine its state (the number of
+    /// independent bits they give).
+    Underdetermined(usize),
+    /// The observed outputs of a generator contradict each other.
+    InconsistentObservations,
+    /// The observed outputs of a generator are spread over too many outputs to solve for (the
+    /// number of outputs from the first observation to the last).
+    ObservationSpanTooLong(u64),
+    /// An error reported by OpenSSL.
+    #[cfg(feature = "openssl")]
+    OpenSsl(openssl::error::ErrorStack),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHex => write!(f, "invalid hex string"),
+            Error::InvalidBase64 => write!(f, "invalid base64 string"),
+            Error::InvalidUrlEncoding => write!(f, "invalid %-code"),
+            Error::MalformedCookie => write!(f, "each kv-pair must be of the form X=Y"),
+            Error::BadPadding => write!(f, "bad padding"),
+            Error::BadKeyLength(n) => write!(f, "unsupported key length: {}", n),
+            Error::BadIvLength(n) => write!(f, "wrong IV or nonce length: {}", n),
+            Error::BadTag => write!(f, "authentication tag mismatch"),
+            Error::BadInputLength(n) => {
+                write!(f, "input length {} is not a multiple of the block size", n)
+            }
+            Error::BadOutputLength(n) => write!(f, "cannot derive {} bytes", n),
+            Error::BadIterationCount(n) => write!(f, "unsupported iteration count: {}", n),
+            Error::InvalidModel => write!(f, "malformed n-gram model"),
+            Error::Underdetermined(n) => {
+                write!(f, "the observations only give {} independent bits", n)
+            }
+            Error::InconsistentObservations => write!(f, "inconsistent observations"),
+            Error::ObservationSpanTooLong(n) => {
+                write!(f, "the observations span too many outputs: {}", n)
+            }
+            #[cfg(feature = "openssl")]
+            Error::OpenSsl(e) => write!(f, "openssl error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(feature = "openssl")]
+impl From<openssl::error::ErrorStack> for Error {
+    fn from(e: openssl::error::ErrorStack) -> Self {
+        Error::OpenSsl(e)
+    }
+}
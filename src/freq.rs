@@ -30,3 +30,157 @@ pub fn load_expected_letter_freq() -> AsciiFreq {
 pub fn dist(a: &[f64], b: &[f64]) -> f64 {
     a.iter().zip(b).map(|(&x, &y)| (x - y).abs()).sum()
 }
+
+/// Scores candidate plaintexts, for choosing among decryptions. Lower is better.
+pub trait Scorer {
+    fn score(&self, text: &[u8]) -> f64;
+}
+
+/// Any function of the text can be used as a scorer.
+impl<F: Fn(&[u8]) -> f64> Scorer for F {
+    fn score(&self, text: &[u8]) -> f64 {
+        self(text)
+    }
+}
+
+/// Probability given to bytes that never occur in the expected distribution.
+const FLOOR: f64 = 1e-8;
+
+/// Frequencies of each byte in the text.
+fn byte_freq(text: &[u8]) -> [f64; 256] {
+    let mut freq = [0.0; 256];
+    for &c in text {
+        freq[c as usize] += 1.0;
+    }
+    for x in &mut freq {
+        *x /= text.len() as f64;
+    }
+    freq
+}
+
+/// L1 distance between the ASCII frequencies of the text and the expected ones.
+/// Texts with bytes outside ASCII are rejected with an infinite score.
+pub struct L1 {
+    expected: AsciiFreq,
+}
+
+impl L1 {
+    pub fn new(expected: AsciiFreq) -> Self {
+        Self { expected }
+    }
+
+    /// Compares with English text.
+    pub fn english() -> Self {
+        Self::new(load_expected_freq())
+    }
+}
+
+impl Scorer for L1 {
+    fn score(&self, text: &[u8]) -> f64 {
+        if !text.iter().all(|&c| c < 128) {
+            return f64::INFINITY;
+        }
+        dist(&byte_freq(text)[..128], &self.expected)
+    }
+}
+
+/// Pearson's chi-squared statistic of the byte counts against the expected frequencies,
+/// normalized by the text length.
+pub struct ChiSquared {
+    expected: AsciiFreq,
+}
+
+impl ChiSquared {
+    pub fn new(expected: AsciiFreq) -> Self {
+        Self { expected }
+    }
+
+    /// Compares with English text.
+    pub fn english() -> Self {
+        Self::new(load_expected_freq())
+    }
+}
+
+impl Scorer for ChiSquared {
+    fn score(&self, text: &[u8]) -> f64 {
+        let freq = byte_freq(text);
+        (0..256)
+            .map(|c| {
+                let e = self.expected.get(c).copied().unwrap_or(0.0).max(FLOOR);
+                (freq[c] - e).powi(2) / e
+            })
+            .sum()
+    }
+}
+
+/// Negative log-likelihood per byte of the text, with the bytes drawn independently from the
+/// expected distribution. Unexpected bytes are penalized but not rejected.
+pub struct LogLikelihood {
+    log_probs: [f64; 256],
+}
+
+impl LogLikelihood {
+    pub fn new(expected: AsciiFreq) -> Self {
+        let mut log_probs = [FLOOR.ln(); 256];
+        for (c, &f) in expected.iter().enumerate() {
+            log_probs[c] = f.max(FLOOR).ln();
+        }
+        Self { log_probs }
+    }
+
+    /// Compares with English text.
+    pub fn english() -> Self {
+        Self::new(load_expected_freq())
+    }
+}
+
+impl Scorer for LogLikelihood {
+    fn score(&self, text: &[u8]) -> f64 {
+        let total: f64 = text.iter().map(|&c| self.log_probs[c as usize]).sum();
+        -total / text.len() as f64
+    }
+}
+
+/// The fraction of bytes that are not printable ASCII or whitespace.
+/// Makes no assumption on the language.
+pub struct PrintableRatio;
+
+impl Scorer for PrintableRatio {
+    fn score(&self, text: &[u8]) -> f64 {
+        Predicate(|c: u8| c.is_ascii_graphic() || c.is_ascii_whitespace()).score(text)
+    }
+}
+
+/// The fraction of bytes that do not satisfy the predicate.
+pub struct Predicate<F: Fn(u8) -> bool>(pub F);
+
+impl<F: Fn(u8) -> bool> Scorer for Predicate<F> {
+    fn score(&self, text: &[u8]) -> f64 {
+        let bad = text.iter().filter(|&&c| !(self.0)(c)).count();
+        bad as f64 / text.len() as f64
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn scorers_test() {
+        let english = b"The quick brown fox jumps over the lazy dog, and then some more.";
+        let garbled: Vec<u8> = english.iter().map(|&c| c ^ 0x55).collect();
+        let scorers: [&dyn Scorer; 5] = [
+            &L1::english(),
+            &ChiSquared::english(),
+            &LogLikelihood::english(),
+            &PrintableRatio,
+            &|text: &[u8]| -(text.iter().filter(|&&c| c == b' ').count() as f64),
+        ];
+        for scorer in scorers {
+            assert!(scorer.score(english) < scorer.score(&garbled));
+        }
+        assert_eq!(L1::english().score(&[0x80]), f64::INFINITY);
+        assert!(LogLikelihood::english().score(&[0x80]).is_finite());
+        assert_eq!(Predicate(|c: u8| c.is_ascii_digit()).score(b"a1b2"), 0.5);
+    }
+}
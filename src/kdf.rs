@@ -0,0 +1,217 @@
+use num_bigint::BigUint;
+
+use crate::{
+    error::{Error, Result},
+    mac::{hmac, sha1, Hasher, Hmac, Sha256},
+};
+
+/// PBKDF2 (RFC 8018) with HMAC over the hasher: derives a key of len bytes from a password.
+/// At least one iteration is needed.
+pub fn pbkdf2<H: Hasher>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    len: usize,
+) -> Result<Vec<u8>> {
+    if iterations == 0 {
+        return Err(Error::BadIterationCount(iterations));
+    }
+    // The password is only processed once, and the keyed HMAC cloned for every round.
+    let prf = Hmac::<H>::new(password);
+    let mut res = Vec::with_capacity(len);
+    let mut index = 1u32;
+    while res.len() < len {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&index.to_be_bytes());
+        let mut u = mac.finalize();
+        let mut block = u.as_ref().to_vec();
+        for _ in 1..iterations {
+            let mut mac = prf.clone();
+            mac.update(u.as_ref());
+            u = mac.finalize();
+            for (b, &x) in block.iter_mut().zip(u.as_ref()) {
+                *b ^= x;
+            }
+        }
+        let take = block.len().min(len - res.len());
+        res.extend_from_slice(&block[..take]);
+        index += 1;
+    }
+    Ok(res)
+}
+
+/// The extract step of HKDF (RFC 5869): concentrates the entropy of the input keying material
+/// into a pseudorandom key. An empty salt stands for a string of zeros.
+pub fn hkdf_extract<H: Hasher>(salt: &[u8], ikm: &[u8]) -> H::Digest {
+    hmac::<H>(salt, ikm)
+}
+
+/// The expand step of HKDF: derives len bytes bound to the context info from a pseudorandom key.
+/// At most 255 times the digest size can be derived.
+pub fn hkdf_expand<H: Hasher>(prk: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>> {
+    let mut res = Vec::with_capacity(len);
+    let mut t = vec![];
+    for index in 1..=255u8 {
+        if res.len() >= len {
+            break;
+        }
+        let mut mac = Hmac::<H>::new(prk);
+        mac.update(&t);
+        mac.update(info);
+        mac.update(&[index]);
+        t = mac.finalize().as_ref().to_vec();
+        let take = t.len().min(len - res.len());
+        res.extend_from_slice(&t[..take]);
+    }
+    if res.len() < len {
+        return Err(Error::BadOutputLength(len));
+    }
+    Ok(res)
+}
+
+/// HKDF: extract then expand.
+pub fn hkdf<H: Hasher>(salt: &[u8], ikm: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>> {
+    hkdf_expand::<H>(hkdf_extract::<H>(salt, ikm).as_ref(), info, len)
+}
+
+/// How a Diffie-Hellman shared secret is turned into a symmetric key.
+/// The secret is taken as its big-endian bytes.
+#[derive(Clone, Debug)]
+pub enum KeyDerivation {
+    /// A prefix of the SHA-1 of the secret, as the challenges do. At most 20 bytes.
+    Sha1,
+    /// HKDF-SHA256.
+    Hkdf { salt: Vec<u8>, info: Vec<u8> },
+    /// PBKDF2-HMAC-SHA256, for secrets that may be guessable.
+    Pbkdf2 { salt: Vec<u8>, iterations: u32 },
+}
+
+impl KeyDerivation {
+    /// Derives a key of len bytes, e.g. 16 for AES-128.
+    pub fn derive(&self, secret: &BigUint, len: usize) -> Result<Vec<u8>> {
+        let secret = secret.to_bytes_be();
+        match self {
+            KeyDerivation::Sha1 => {
+                let hash = sha1(&secret);
+                hash.get(..len)
+                    .map(|k| k.to_vec())
+                    .ok_or(Error::BadOutputLength(len))
+            }
+            KeyDerivation::Hkdf { salt, info } => hkdf::<Sha256>(salt, &secret, info, len),
+            KeyDerivation::Pbkdf2 { salt, iterations } => {
+                pbkdf2::<Sha256>(&secret, salt, *iterations, len)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{convert::to_hex, mac::Sha1};
+
+    use super::*;
+
+    #[test]
+    fn pbkdf2_test() {
+        // RFC 6070
+        let expected = [
+            (1, "0C60C80F961F0E71F3A9B524AF6012062FE037A6"),
+            (2, "EA6C014DC72D6F8CCD1ED92ACE1D41F0D8DE8957"),
+            (4096, "4B007901B765489ABEAD49D926F721D065A429C1"),
+        ];
+        for (iterations, expected) in expected {
+            assert_eq!(
+                to_hex(&pbkdf2::<Sha1>(b"password", b"salt", iterations, 20).unwrap()),
+                expected
+            );
+        }
+        assert_eq!(
+            to_hex(
+                &pbkdf2::<Sha1>(
+                    b"passwordPASSWORDpassword",
+                    b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                    4096,
+                    25
+                )
+                .unwrap()
+            ),
+            "3D2EEC4FE41C849B80C8D83662C0E44A8B291A964CF2F07038"
+        );
+        assert_eq!(
+            to_hex(&pbkdf2::<Sha256>(b"password", b"salt", 2, 32).unwrap()),
+            "AE4D0C95AF6B46D32D0ADFF928F06DD02A303F8EF3C251DFD6E2D85A95474C43"
+        );
+        assert!(matches!(
+            pbkdf2::<Sha1>(b"password", b"salt", 0, 20),
+            Err(Error::BadIterationCount(0))
+        ));
+    }
+
+    #[test]
+    fn hkdf_test() {
+        // RFC 5869, test cases 1 and 3
+        let ikm = [0x0b; 22];
+        let salt: Vec<u8> = (0..13).collect();
+        let info: Vec<u8> = (0xf0..0xfa).collect();
+        assert_eq!(
+            to_hex(&hkdf_extract::<Sha256>(&salt, &ikm)),
+            "077709362C2E32DF0DDC3F0DC47BBA6390B6C73BB50F9C3122EC844AD7C2B3E5"
+        );
+        assert_eq!(
+            to_hex(&hkdf::<Sha256>(&salt, &ikm, &info, 42).unwrap()),
+            "3CB25F25FAACD57A90434F64D0362F2A2D2D0A90CF1A5A4C5DB02D56ECC4C5BF\
+             34007208D5B887185865"
+        );
+        assert_eq!(
+            to_hex(&hkdf_extract::<Sha256>(b"", &ikm)),
+            "19EF24A32C717B167F33A91D6F648BDF96596776AFDB6377AC434C1C293CCB04"
+        );
+        assert_eq!(
+            to_hex(&hkdf::<Sha256>(b"", &ikm, b"", 42).unwrap()),
+            "8DA4E775A563C18F715F802A063C5A31B8A11F5C5EE1879EC3454E5F3C738D2D\
+             9D201395FAA4B61A96C8"
+        );
+        assert_eq!(
+            hkdf::<Sha256>(b"", &ikm, b"", 255 * 32).unwrap().len(),
+            255 * 32
+        );
+        assert!(matches!(
+            hkdf::<Sha256>(b"", &ikm, b"", 255 * 32 + 1),
+            Err(Error::BadOutputLength(8161))
+        ));
+    }
+
+    #[test]
+    fn key_derivation_test() {
+        let secret = BigUint::from(0xdeadbeefu32);
+        let key = KeyDerivation::Sha1.derive(&secret, 16).unwrap();
+        assert_eq!(key, sha1(&[0xde, 0xad, 0xbe, 0xef])[..16]);
+        assert!(matches!(
+            KeyDerivation::Sha1.derive(&secret, 21),
+            Err(Error::BadOutputLength(21))
+        ));
+
+        let hkdf = KeyDerivation::Hkdf {
+            salt: b"salt".to_vec(),
+            info: b"AES key".to_vec(),
+        };
+        assert_eq!(hkdf.derive(&secret, 16).unwrap().len(), 16);
+        let pbkdf2 = KeyDerivation::Pbkdf2 {
+            salt: b"salt".to_vec(),
+            iterations: 10,
+        };
+        assert_ne!(
+            pbkdf2.derive(&secret, 16).unwrap(),
+            hkdf.derive(&secret, 16).unwrap()
+        );
+        let no_iterations = KeyDerivation::Pbkdf2 {
+            salt: b"salt".to_vec(),
+            iterations: 0,
+        };
+        assert!(matches!(
+            no_iterations.derive(&secret, 16),
+            Err(Error::BadIterationCount(0))
+        ));
+    }
+}
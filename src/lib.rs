@@ -0,0 +1,16 @@
+#![allow(clippy::approx_constant)]
+#![allow(clippy::new_without_default)]
+#![allow(clippy::needless_range_loop)]
+
+pub mod ciphers;
+pub mod convert;
+pub mod crib;
+pub mod error;
+pub mod freq;
+pub mod kdf;
+pub mod mac;
+pub mod mersenne;
+pub mod ngram;
+pub mod num;
+pub mod oracles;
+pub mod util;
@@ -0,0 +1,179 @@
+use crate::mac::Hasher;
+
+/// Streaming HMAC (RFC 2104) over any of the hashers.
+/// Cloning a freshly keyed Hmac saves processing the key again for each message.
+#[derive(Clone)]
+pub struct Hmac<H: Hasher> {
+    inner: H,
+    /// A hasher that has already been fed the key XORed with the outer pad.
+    outer: H,
+}
+
+impl<H: Hasher> Hmac<H> {
+    /// Keys longer than the block size are hashed first.
+    pub fn new(key: &[u8]) -> Self {
+        let mut block_key = if key.len() > H::BLOCK_SIZE {
+            H::digest(key).as_ref().to_vec()
+        } else {
+            key.to_vec()
+        };
+        block_key.resize(H::BLOCK_SIZE, 0);
+        let inner_key: Vec<u8> = block_key.iter().map(|&c| c ^ 0x36).collect();
+        let outer_key: Vec<u8> = block_key.iter().map(|&c| c ^ 0x5c).collect();
+        let mut inner = H::new();
+        inner.update(&inner_key);
+        let mut outer = H::new();
+        outer.update(&outer_key);
+        Self { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> H::Digest {
+        let mut outer = self.outer;
+        outer.update(self.inner.finalize().as_ref());
+        outer.finalize()
+    }
+
+    /// Checks the MAC of everything fed so far, in constant time.
+    pub fn verify(self, mac: &[u8]) -> bool {
+        constant_time_eq(self.finalize().as_ref(), mac)
+    }
+}
+
+pub fn hmac<H: Hasher>(key: &[u8], msg: &[u8]) -> H::Digest {
+    let mut hmac = Hmac::<H>::new(key);
+    hmac.update(msg);
+    hmac.finalize()
+}
+
+/// Checks the HMAC of a message in constant time.
+pub fn verify_hmac<H: Hasher>(key: &[u8], msg: &[u8], mac: &[u8]) -> bool {
+    let mut hmac = Hmac::<H>::new(key);
+    hmac.update(msg);
+    hmac.verify(mac)
+}
+
+/// Compares two byte strings in time that depends only on their lengths, unlike the early-exit
+/// comparison exploited in challenges 31 and 32.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0, |acc, (&x, &y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        convert::to_hex,
+        mac::{Md5, Sha1, Sha224, Sha256, Sha384, Sha512},
+    };
+
+    use super::*;
+
+    /// Test cases 1, 2 and 6 of RFC 4231.
+    fn rfc4231_cases() -> [(Vec<u8>, &'static [u8]); 3] {
+        [
+            (vec![0x0b; 20], b"Hi There"),
+            (b"Jefe".to_vec(), b"what do ya want for nothing?"),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+            ),
+        ]
+    }
+
+    #[test]
+    fn rfc4231_test() {
+        let expected = [
+            [
+                "896FB1128ABBDF196832107CD49DF33F47B4B1169912BA4F53684B22",
+                "B0344C61D8DB38535CA8AFCEAF0BF12B881DC200C9833DA726E9376C2E32CFF7",
+                "AFD03944D84895626B0825F4AB46907F15F9DADBE4101EC682AA034C7CEBC59C\
+                 FAEA9EA9076EDE7F4AF152E8B2FA9CB6",
+                "87AA7CDEA5EF619D4FF0B4241A1D6CB02379F4E2CE4EC2787AD0B30545E17CDE\
+                 DAA833B7D6B8A702038B274EAEA3F4E4BE9D914EEB61F1702E696C203A126854",
+            ],
+            [
+                "A30E01098BC6DBBF45690F3A7E9E6D0F8BBEA2A39E6148008FD05E44",
+                "5BDCC146BF60754E6A042426089575C75A003F089D2739839DEC58B964EC3843",
+                "AF45D2E376484031617F78D2B58A6B1B9C7EF464F5A01B47E42EC3736322445E\
+                 8E2240CA5E69E2C78B3239ECFAB21649",
+                "164B7A7BFCF819E2E395FBE73B56E0A387BD64222E831FD610270CD7EA250554\
+                 9758BF75C05A994A6D034F65F8F0E6FDCAEAB1A34D4A6B4B636E070A38BCE737",
+            ],
+            [
+                "95E9A0DB962095ADAEBE9B2D6F0DBCE2D499F112F2D2B7273FA6870E",
+                "60E431591EE0B67F0D8A26AACBF5B77F8E0BC6213728C5140546040F0EE37F54",
+                "4ECE084485813E9088D2C63A041BC5B44F9EF1012A2B588F3CD11F05033AC4C6\
+                 0C2EF6AB4030FE8296248DF163F44952",
+                "80B24263C7C1A3EBB71493C1DD7BE8B49B46D1F41B4AEEC1121B013783F8F352\
+                 6B56D037E05F2598BD0FD2215D6A1E5295E64F73F63F0AEC8B915A985D786598",
+            ],
+        ];
+        for ((key, msg), expected) in rfc4231_cases().iter().zip(expected) {
+            assert_eq!(to_hex(&hmac::<Sha224>(key, msg)), expected[0]);
+            assert_eq!(to_hex(&hmac::<Sha256>(key, msg)), expected[1]);
+            assert_eq!(to_hex(&hmac::<Sha384>(key, msg)), expected[2]);
+            assert_eq!(to_hex(&hmac::<Sha512>(key, msg)), expected[3]);
+        }
+    }
+
+    #[test]
+    fn rfc2202_test() {
+        assert_eq!(
+            to_hex(&hmac::<Sha1>(&[0x0b; 20], b"Hi There")),
+            "B617318655057264E28BC0B6FB378C8EF146BE00"
+        );
+        assert_eq!(
+            to_hex(&hmac::<Sha1>(b"Jefe", b"what do ya want for nothing?")),
+            "EFFCDF6AE5EB2FA2D27416D5F184DF9C259A7C79"
+        );
+        let long_msg = b"Test Using Larger Than Block-Size Key - Hash Key First";
+        assert_eq!(
+            to_hex(&hmac::<Sha1>(&[0xaa; 80], long_msg)),
+            "AA4AE5E15272D00E95705637CE8A3B55ED402112"
+        );
+        assert_eq!(
+            to_hex(&hmac::<Md5>(&[0x0b; 16], b"Hi There")),
+            "9294727A3638BB1C13F48EF8158BFC9D"
+        );
+        assert_eq!(
+            to_hex(&hmac::<Md5>(b"Jefe", b"what do ya want for nothing?")),
+            "750C783E6AB0B503EAA86E310A5DB738"
+        );
+        assert_eq!(
+            to_hex(&hmac::<Md5>(&[0xaa; 80], long_msg)),
+            "6B1AB7FE4BD7BF8F0B62E6CE61B9D0CD"
+        );
+    }
+
+    #[test]
+    fn verify_test() {
+        let mac = hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?");
+        assert!(verify_hmac::<Sha256>(
+            b"Jefe",
+            b"what do ya want for nothing?",
+            &mac
+        ));
+        assert!(!verify_hmac::<Sha256>(
+            b"Jefe",
+            b"what do ya want for nothing!",
+            &mac
+        ));
+        assert!(!verify_hmac::<Sha256>(
+            b"Jefe",
+            b"what do ya want for nothing?",
+            &mac[..31]
+        ));
+
+        let mut streaming = Hmac::<Sha256>::new(b"Jefe");
+        streaming.update(b"what do ya ");
+        streaming.update(b"want for nothing?");
+        assert!(streaming.verify(&mac));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::mac::{md_padding, BlockBuffer, Hasher};
+
+pub const INITIAL_STATE: [u32; 4] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+
+/// The MD4 compression function: updates the state with a 64-byte block.
+pub fn compress(state: &mut [u32; 4], block: &[u8]) {
+    assert_eq!(block.len(), 64);
+    let mut w = [0u32; 16];
+    for i in 0..16 {
+        w[i] = u32::from_le_bytes(block[i * 4..(i + 1) * 4].try_into().unwrap());
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    let f = |x: u32, y: u32, z: u32| (x & y) | ((!x) & z);
+    let g = |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z);
+    let h = |x: u32, y: u32, z: u32| x ^ y ^ z;
+
+    let params = [
+        [0, 3, 1, 7, 2, 11, 3, 19],
+        [4, 3, 5, 7, 6, 11, 7, 19],
+        [8, 3, 9, 7, 10, 11, 11, 19],
+        [12, 3, 13, 7, 14, 11, 15, 19],
+    ];
+
+    for p in params {
+        a = a
+            .wrapping_add(f(b, c, d))
+            .wrapping_add(w[p[0]])
+            .rotate_left(p[1] as u32);
+        d = d
+            .wrapping_add(f(a, b, c))
+            .wrapping_add(w[p[2]])
+            .rotate_left(p[3] as u32);
+        c = c
+            .wrapping_add(f(d, a, b))
+            .wrapping_add(w[p[4]])
+            .rotate_left(p[5] as u32);
+        b = b
+            .wrapping_add(f(c, d, a))
+            .wrapping_add(w[p[6]])
+            .rotate_left(p[7] as u32);
+    }
+    let params = [
+        [0, 3, 4, 5, 8, 9, 12, 13],
+        [1, 3, 5, 5, 9, 9, 13, 13],
+        [2, 3, 6, 5, 10, 9, 14, 13],
+        [3, 3, 7, 5, 11, 9, 15, 13],
+    ];
+    for p in params {
+        let z = 0x5A827999u32;
+        a = a
+            .wrapping_add(g(b, c, d))
+            .wrapping_add(w[p[0]])
+            .wrapping_add(z)
+            .rotate_left(p[1] as u32);
+        d = d
+            .wrapping_add(g(a, b, c))
+            .wrapping_add(w[p[2]])
+            .wrapping_add(z)
+            .rotate_left(p[3] as u32);
+        c = c
+            .wrapping_add(g(d, a, b))
+            .wrapping_add(w[p[4]])
+            .wrapping_add(z)
+            .rotate_left(p[5] as u32);
+        b = b
+            .wrapping_add(g(c, d, a))
+            .wrapping_add(w[p[6]])
+            .wrapping_add(z)
+            .rotate_left(p[7] as u32);
+    }
+    let params = [
+        [0, 3, 8, 9, 4, 11, 12, 15],
+        [2, 3, 10, 9, 6, 11, 14, 15],
+        [1, 3, 9, 9, 5, 11, 13, 15],
+        [3, 3, 11, 9, 7, 11, 15, 15],
+    ];
+    for p in params {
+        let z = 0x6ED9EBA1u32;
+        a = a
+            .wrapping_add(h(b, c, d))
+            .wrapping_add(w[p[0]])
+            .wrapping_add(z)
+            .rotate_left(p[1] as u32);
+        d = d
+            .wrapping_add(h(a, b, c))
+            .wrapping_add(w[p[2]])
+            .wrapping_add(z)
+            .rotate_left(p[3] as u32);
+        c = c
+            .wrapping_add(h(d, a, b))
+            .wrapping_add(w[p[4]])
+            .wrapping_add(z)
+            .rotate_left(p[5] as u32);
+        b = b
+            .wrapping_add(h(c, d, a))
+            .wrapping_add(w[p[6]])
+            .wrapping_add(z)
+            .rotate_left(p[7] as u32);
+    }
+    for (h, x) in state.iter_mut().zip([a, b, c, d]) {
+        *h = h.wrapping_add(x);
+    }
+}
+
+/// Streaming MD4.
+#[derive(Clone)]
+pub struct Md4 {
+    state: [u32; 4],
+    buffer: BlockBuffer,
+}
+
+impl Hasher for Md4 {
+    const BLOCK_SIZE: usize = 64;
+    type State = [u32; 4];
+    type Digest = [u8; 16];
+
+    fn new() -> Self {
+        Self::from_state(INITIAL_STATE, 0).unwrap()
+    }
+
+    fn from_state(state: Self::State, processed_len: u64) -> Option<Self> {
+        Some(Self {
+            state,
+            buffer: BlockBuffer::new(Self::BLOCK_SIZE, processed_len)?,
+        })
+    }
+
+    fn state_from_digest(digest: &Self::Digest) -> Option<Self::State> {
+        let mut state = [0; 4];
+        for (h, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+            *h = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Some(state)
+    }
+
+    fn padding(len: u64) -> Vec<u8> {
+        md_padding(len, Self::BLOCK_SIZE, false)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.feed(data, |block| compress(state, block));
+    }
+
+    fn finalize(mut self) -> Self::Digest {
+        let padding = Self::padding(self.buffer.len());
+        self.update(&padding);
+        let mut res = [0u8; 16];
+        for (chunk, h) in res.chunks_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&h.to_le_bytes());
+        }
+        res
+    }
+}
+
+pub fn md4(msg: &[u8]) -> [u8; 16] {
+    Md4::digest(msg)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::convert::to_hex;
+
+    use super::*;
+
+    #[test]
+    fn test_md4() {
+        assert_eq!(
+            to_hex(&md4(b"The quick brown fox jumps over the lazy dog")),
+            "1BEE69A46BA811185C194762ABAEAE90"
+        );
+        assert_eq!(
+            to_hex(&md4(b"The quick brown fox jumps over the lazy cog")),
+            "B86E130CE7028DA59E672D56AD0113DF"
+        );
+        assert_eq!(to_hex(&md4(b"")), "31D6CFE0D16AE931B73C59D7E0C089C0");
+    }
+}
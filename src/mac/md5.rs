@@ -0,0 +1,127 @@
+use crate::mac::{md_padding, BlockBuffer, Hasher};
+
+pub const INITIAL_STATE: [u32; 4] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+
+/// K[i] is the integer part of |sin(i + 1)| * 2^32.
+const K: [u32; 64] = [
+    0xD76AA478, 0xE8C7B756, 0x242070DB, 0xC1BDCEEE, 0xF57C0FAF, 0x4787C62A, 0xA8304613, 0xFD469501,
+    0x698098D8, 0x8B44F7AF, 0xFFFF5BB1, 0x895CD7BE, 0x6B901122, 0xFD987193, 0xA679438E, 0x49B40821,
+    0xF61E2562, 0xC040B340, 0x265E5A51, 0xE9B6C7AA, 0xD62F105D, 0x02441453, 0xD8A1E681, 0xE7D3FBC8,
+    0x21E1CDE6, 0xC33707D6, 0xF4D50D87, 0x455A14ED, 0xA9E3E905, 0xFCEFA3F8, 0x676F02D9, 0x8D2A4C8A,
+    0xFFFA3942, 0x8771F681, 0x6D9D6122, 0xFDE5380C, 0xA4BEEA44, 0x4BDECFA9, 0xF6BB4B60, 0xBEBFBC70,
+    0x289B7EC6, 0xEAA127FA, 0xD4EF3085, 0x04881D05, 0xD9D4D039, 0xE6DB99E5, 0x1FA27CF8, 0xC4AC5665,
+    0xF4292244, 0x432AFF97, 0xAB9423A7, 0xFC93A039, 0x655B59C3, 0x8F0CCC92, 0xFFEFF47D, 0x85845DD1,
+    0x6FA87E4F, 0xFE2CE6E0, 0xA3014314, 0x4E0811A1, 0xF7537E82, 0xBD3AF235, 0x2AD7D2BB, 0xEB86D391,
+];
+
+/// Rotation amounts, per round and step.
+const SHIFTS: [[u32; 4]; 4] = [
+    [7, 12, 17, 22],
+    [5, 9, 14, 20],
+    [4, 11, 16, 23],
+    [6, 10, 15, 21],
+];
+
+/// The MD5 compression function: updates the state with a 64-byte block.
+pub fn compress(state: &mut [u32; 4], block: &[u8]) {
+    assert_eq!(block.len(), 64);
+    let mut w = [0u32; 16];
+    for i in 0..16 {
+        w[i] = u32::from_le_bytes(block[i * 4..(i + 1) * 4].try_into().unwrap());
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | ((!b) & d), i),
+            1 => ((d & b) | ((!d) & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            3 => (c ^ (b | !d), (7 * i) % 16),
+            _ => unreachable!(),
+        };
+        let tmp = a
+            .wrapping_add(f)
+            .wrapping_add(K[i])
+            .wrapping_add(w[g])
+            .rotate_left(SHIFTS[i / 16][i % 4]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(tmp);
+    }
+    for (h, x) in state.iter_mut().zip([a, b, c, d]) {
+        *h = h.wrapping_add(x);
+    }
+}
+
+/// Streaming MD5.
+#[derive(Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: BlockBuffer,
+}
+
+impl Hasher for Md5 {
+    const BLOCK_SIZE: usize = 64;
+    type State = [u32; 4];
+    type Digest = [u8; 16];
+
+    fn new() -> Self {
+        Self::from_state(INITIAL_STATE, 0).unwrap()
+    }
+
+    fn from_state(state: Self::State, processed_len: u64) -> Option<Self> {
+        Some(Self {
+            state,
+            buffer: BlockBuffer::new(Self::BLOCK_SIZE, processed_len)?,
+        })
+    }
+
+    fn state_from_digest(digest: &Self::Digest) -> Option<Self::State> {
+        let mut state = [0; 4];
+        for (h, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+            *h = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Some(state)
+    }
+
+    fn padding(len: u64) -> Vec<u8> {
+        md_padding(len, Self::BLOCK_SIZE, false)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.feed(data, |block| compress(state, block));
+    }
+
+    fn finalize(mut self) -> Self::Digest {
+        let padding = Self::padding(self.buffer.len());
+        self.update(&padding);
+        let mut res = [0u8; 16];
+        for (chunk, h) in res.chunks_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&h.to_le_bytes());
+        }
+        res
+    }
+}
+
+pub fn md5(msg: &[u8]) -> [u8; 16] {
+    Md5::digest(msg)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::convert::to_hex;
+
+    use super::*;
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(
+            to_hex(&md5(b"The quick brown fox jumps over the lazy dog")),
+            "9E107D9D372BB6826BD81D3542A419D6"
+        );
+        assert_eq!(to_hex(&md5(b"abc")), "900150983CD24FB0D6963F7D28E17F72");
+        assert_eq!(to_hex(&md5(b"")), "D41D8CD98F00B204E9800998ECF8427E");
+    }
+}
@@ -0,0 +1,329 @@
+pub mod hmac;
+pub mod md4;
+pub mod md5;
+pub mod sha1;
+pub mod sha2;
+
+pub use hmac::{constant_time_eq, hmac, verify_hmac, Hmac};
+pub use md4::{md4, Md4};
+pub use md5::{md5, Md5};
+pub use sha1::{sha1, Sha1};
+pub use sha2::{sha224, sha256, sha384, sha512, Sha224, Sha256, Sha384, Sha512};
+
+/// A Merkle-Damgard hash function that can be fed incrementally.
+///
+/// The digest is an encoding of the internal state, so a hasher can be resumed from a digest
+/// with from_state, which is what length extension attacks do.
+pub trait Hasher: Sized + Clone {
+    /// Size of the blocks of the compression function, in bytes.
+    const BLOCK_SIZE: usize;
+    /// The chaining state.
+    type State;
+    type Digest: AsRef<[u8]>;
+
+    fn new() -> Self;
+
+    /// Resumes hashing from a state, after processed_len bytes. Returns None if processed_len
+    /// is not a multiple of the block size.
+    fn from_state(state: Self::State, processed_len: u64) -> Option<Self>;
+
+    /// The state encoded by a digest, or None if the digest is truncated and does not
+    /// determine it.
+    fn state_from_digest(digest: &Self::Digest) -> Option<Self::State>;
+
+    /// The padding appended to a message of len bytes before the final compression.
+    fn padding(len: u64) -> Vec<u8>;
+
+    fn update(&mut self, data: &[u8]);
+
+    /// Pads the message and returns the digest.
+    fn finalize(self) -> Self::Digest;
+
+    /// Hashes a whole message.
+    fn digest(msg: &[u8]) -> Self::Digest {
+        let mut hasher = Self::new();
+        hasher.update(msg);
+        hasher.finalize()
+    }
+}
+
+/// The padding appended to a message of len bytes: a 1-bit, 0-bits, and the message length in
+/// bits, in the last eighth of a block.
+/// If "be" is true, the length will be in big-endian format, otherwise little-endian.
+pub fn md_padding(len: u64, block_size: usize, be: bool) -> Vec<u8> {
+    let length_size = block_size / 8;
+    let bits = len as u128 * 8;
+    let mut res = vec![0x80];
+    let used = (len as usize + 1) % block_size;
+    let zeros = (2 * block_size - length_size - used) % block_size;
+    res.resize(1 + zeros, 0);
+    if be {
+        res.extend_from_slice(&bits.to_be_bytes()[16 - length_size..]);
+    } else {
+        res.extend_from_slice(&bits.to_le_bytes()[..length_size]);
+    }
+    res
+}
+
+/// Collects input into whole blocks for a compression function.
+#[derive(Clone)]
+pub(crate) struct BlockBuffer {
+    block_size: usize,
+    buf: Vec<u8>,
+    len: u64,
+}
+
+impl BlockBuffer {
+    /// Returns None unless processed_len is at a block boundary, the only place to resume from.
+    pub(crate) fn new(block_size: usize, processed_len: u64) -> Option<Self> {
+        if !processed_len.is_multiple_of(block_size as u64) {
+            return None;
+        }
+        Some(Self {
+            block_size,
+            buf: Vec::with_capacity(block_size),
+            len: processed_len,
+        })
+    }
+
+    /// The total number of bytes fed so far.
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Calls compress on each block completed by the data.
+    pub(crate) fn feed(&mut self, mut data: &[u8], mut compress: impl FnMut(&[u8])) {
+        self.len += data.len() as u64;
+        if !self.buf.is_empty() {
+            let take = data.len().min(self.block_size - self.buf.len());
+            self.buf.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.buf.len() < self.block_size {
+                return;
+            }
+            compress(&self.buf);
+            self.buf.clear();
+        }
+        let mut blocks = data.chunks_exact(self.block_size);
+        for block in &mut blocks {
+            compress(block);
+        }
+        self.buf.extend_from_slice(blocks.remainder());
+    }
+}
+
+/// Takes the hash of a message and computes the hash of the same message, extended with the
+/// suffix after the message and its padding.
+/// Requires knowing the total length of the combined message (original message + padding +
+/// suffix). Returns None if the digest is truncated and cannot be resumed from, or if the total
+/// length does not put the suffix right after whole blocks.
+pub fn extend<H: Hasher>(digest: &H::Digest, suffix: &[u8], total_len: usize) -> Option<H::Digest> {
+    let state = H::state_from_digest(digest)?;
+    let processed_len = total_len.checked_sub(suffix.len())?;
+    let mut hasher = H::from_state(state, processed_len as u64)?;
+    hasher.update(suffix);
+    Some(hasher.finalize())
+}
+
+/// A message and MAC forged by a length extension attack.
+pub struct Forgery<D> {
+    pub message: Vec<u8>,
+    pub mac: D,
+    /// The key length the forgery was built for.
+    pub key_len: usize,
+}
+
+/// Length extension attack on a secret-prefix MAC, hash(key + message).
+/// Given a message and its MAC, forges the MAC of message + glue padding + suffix for each key
+/// length up to max_key_len, and returns the first forgery accepted by the verifier.
+/// Returns None if no key length works, or if the digest is truncated (SHA-224, SHA-384).
+pub fn forge_length_extension<H: Hasher>(
+    msg: &[u8],
+    mac: &H::Digest,
+    suffix: &[u8],
+    max_key_len: usize,
+    mut verify: impl FnMut(&[u8], &H::Digest) -> bool,
+) -> Option<Forgery<H::Digest>> {
+    H::state_from_digest(mac)?;
+    for key_len in 0..=max_key_len {
+        let mut message = msg.to_vec();
+        message.extend(H::padding((key_len + msg.len()) as u64));
+        message.extend_from_slice(suffix);
+        let mac = extend::<H>(mac, suffix, key_len + message.len())?;
+        if verify(&message, &mac) {
+            return Some(Forgery {
+                message,
+                mac,
+                key_len,
+            });
+        }
+    }
+    None
+}
+
+/// Takes the SHA1 hash of a message and computes the SHA1 of the same message, extended with extra
+/// characters after the message and padding.
+/// Requires knowing the total length of the combined message
+/// (original message + padding + suffix)
+/// Returns None if the hash is not 20 bytes long or the lengths do not fit, as in extend.
+pub fn extend_sha1(hash: &[u8], suffix: &[u8], total_len: usize) -> Option<[u8; 20]> {
+    extend::<Sha1>(hash.try_into().ok()?, suffix, total_len)
+}
+
+/// Pads the message with a 1-bit, some number of 0-bits, and a number representing the message
+/// length in bits. Output is guaranteed to have length divisible by 64 bytes (512 bits).
+/// If "be" is true, the length will be in big-endian format, otherwise little-endian.
+pub fn pad_with_length(msg: &[u8], ml: usize, be: bool) -> Vec<u8> {
+    let mut s = msg.to_vec();
+    s.push(0x80);
+    let rem = (2 * 64 - 8 - s.len() % 64) % 64;
+    s.resize(s.len() + rem, 0);
+    if be {
+        s.extend_from_slice(&ml.to_be_bytes());
+    } else {
+        s.extend_from_slice(&ml.to_le_bytes());
+    }
+    assert_eq!(s.len() % 64, 0);
+    s
+}
+
+/// Similar to extend_sha1, but for md4
+pub fn extend_md4(hash: &[u8], suffix: &[u8], total_len: usize) -> Option<[u8; 16]> {
+    extend::<Md4>(hash.try_into().ok()?, suffix, total_len)
+}
+
+/// Prefix MAC - hash(key + message)
+pub fn generate_sha1_mac(msg: &[u8], key: &[u8]) -> [u8; 20] {
+    let mut s = key.to_vec();
+    s.extend_from_slice(msg);
+    sha1(&s)
+}
+
+pub fn generate_sha1_hmac(msg: &[u8], key: &[u8]) -> [u8; 20] {
+    hmac::<Sha1>(key, msg)
+}
+
+pub fn verify_sha1_mac(msg: &[u8], key: &[u8], mac: &[u8]) -> bool {
+    constant_time_eq(&generate_sha1_mac(msg, key), mac)
+}
+
+pub fn generate_md4_mac(msg: &[u8], key: &[u8]) -> [u8; 16] {
+    let mut s = key.to_vec();
+    s.extend_from_slice(msg);
+    md4(&s)
+}
+
+pub fn verify_md4_mac(msg: &[u8], key: &[u8], mac: &[u8]) -> bool {
+    constant_time_eq(&generate_md4_mac(msg, key), mac)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_sha1() {
+        let orig_message = b"The quick brown fox";
+        let orig_hash = sha1(orig_message);
+
+        let padded_orig = pad_with_length(orig_message, orig_message.len() * 8, true);
+        let suffix = b" the lazy dog";
+        let combined = {
+            let mut s = padded_orig.clone();
+            s.extend_from_slice(suffix);
+            s
+        };
+        let extended_hash = extend_sha1(&orig_hash, suffix, combined.len());
+        assert_eq!(extended_hash, Some(sha1(&combined)));
+        assert_eq!(extend_sha1(&orig_hash, suffix, combined.len() - 1), None);
+        assert_eq!(extend_sha1(&orig_hash, suffix, suffix.len() - 1), None);
+        assert_eq!(extend_sha1(&orig_hash[..19], suffix, combined.len()), None);
+    }
+    #[test]
+    fn test_extend_md4() {
+        let orig_message = b"The quick brown fox";
+        let orig_hash = md4(orig_message);
+        let mut combined = pad_with_length(orig_message, orig_message.len() * 8, false);
+        let suffix = b" the lazy dog";
+        combined.extend_from_slice(suffix);
+        assert_eq!(
+            extend_md4(&orig_hash, suffix, combined.len()),
+            Some(md4(&combined))
+        );
+        assert_eq!(extend_md4(&orig_hash, suffix, 0), None);
+    }
+
+    /// Checks that feeding a message in pieces, including across block boundaries, and resuming
+    /// from the state in a digest agree with hashing in one go.
+    fn check_streaming<H: Hasher>()
+    where
+        H::Digest: PartialEq + std::fmt::Debug,
+    {
+        let msg = include_bytes!("../../data/funky_music.txt");
+        let expected = H::digest(msg);
+        for chunk_len in [1, 37, H::BLOCK_SIZE - 1, H::BLOCK_SIZE, H::BLOCK_SIZE + 1] {
+            let mut hasher = H::new();
+            for chunk in msg.chunks(chunk_len) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), expected);
+        }
+        let mut hasher = H::new();
+        hasher.update(&msg[..H::BLOCK_SIZE - 3]);
+        hasher.update(&msg[H::BLOCK_SIZE - 3..H::BLOCK_SIZE + 3]);
+        hasher.update(&msg[H::BLOCK_SIZE + 3..]);
+        assert_eq!(hasher.finalize(), expected);
+
+        let prefix = &msg[..100];
+        if let Some(state) = H::state_from_digest(&H::digest(prefix)) {
+            let mut padded = prefix.to_vec();
+            padded.extend(H::padding(prefix.len() as u64));
+            let mut hasher = H::from_state(state, padded.len() as u64).unwrap();
+            hasher.update(&msg[100..]);
+            padded.extend_from_slice(&msg[100..]);
+            assert_eq!(hasher.finalize(), H::digest(&padded));
+        }
+    }
+
+    #[test]
+    fn streaming_test() {
+        check_streaming::<Md4>();
+        check_streaming::<Md5>();
+        check_streaming::<Sha1>();
+        check_streaming::<Sha224>();
+        check_streaming::<Sha256>();
+        check_streaming::<Sha384>();
+        check_streaming::<Sha512>();
+    }
+
+    #[test]
+    fn forge_length_extension_test() {
+        let key = b"correct horse battery";
+        let msg = b"user=alice;role=guest";
+        let mac = sha256(&[&key[..], msg].concat());
+        let verify = |m: &[u8], mac: &[u8; 32]| sha256(&[&key[..], m].concat()) == *mac;
+        let forgery =
+            forge_length_extension::<Sha256>(msg, &mac, b";role=admin", 32, verify).unwrap();
+        assert_eq!(forgery.key_len, key.len());
+        assert!(forgery.message.starts_with(msg));
+        assert!(forgery.message.ends_with(b";role=admin"));
+        assert!(verify(&forgery.message, &forgery.mac));
+
+        assert!(forge_length_extension::<Sha256>(msg, &mac, b"x", key.len() - 1, verify).is_none());
+        let mac = sha384(&[&key[..], msg].concat());
+        assert!(forge_length_extension::<Sha384>(msg, &mac, b"x", 32, |_, _| true).is_none());
+    }
+
+    #[test]
+    fn md_padding_test() {
+        for len in 0..200 {
+            let msg = vec![b'a'; len];
+            let mut padded = msg.clone();
+            padded.extend(md_padding(len as u64, 64, true));
+            assert_eq!(padded, pad_with_length(&msg, len * 8, true));
+            let padding = md_padding(len as u64, 128, false);
+            assert_eq!((len + padding.len()) % 128, 0);
+            assert_eq!(padding[padding.len() - 16], (len * 8) as u8);
+        }
+    }
+}
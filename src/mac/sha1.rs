@@ -0,0 +1,118 @@
+use crate::mac::{md_padding, BlockBuffer, Hasher};
+
+pub const INITIAL_STATE: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+/// The SHA-1 compression function: updates the state with a 64-byte block.
+pub fn compress(state: &mut [u32; 5], block: &[u8]) {
+    assert_eq!(block.len(), 64);
+    let mut w = [0u32; 80];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes(block[i * 4..(i + 1) * 4].try_into().unwrap());
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for i in 0..80 {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | ((!b) & d), 0x5A827999u32),
+            20..=39 => (b ^ c ^ d, 0x6ED9EBA1u32),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDCu32),
+            60..=79 => (b ^ c ^ d, 0xCA62C1D6u32),
+            _ => unreachable!(),
+        };
+        let tmp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = tmp;
+    }
+    for (h, x) in state.iter_mut().zip([a, b, c, d, e]) {
+        *h = h.wrapping_add(x);
+    }
+}
+
+/// Streaming SHA-1.
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: BlockBuffer,
+}
+
+impl Hasher for Sha1 {
+    const BLOCK_SIZE: usize = 64;
+    type State = [u32; 5];
+    type Digest = [u8; 20];
+
+    fn new() -> Self {
+        Self::from_state(INITIAL_STATE, 0).unwrap()
+    }
+
+    fn from_state(state: Self::State, processed_len: u64) -> Option<Self> {
+        Some(Self {
+            state,
+            buffer: BlockBuffer::new(Self::BLOCK_SIZE, processed_len)?,
+        })
+    }
+
+    fn state_from_digest(digest: &Self::Digest) -> Option<Self::State> {
+        let mut state = [0; 5];
+        for (h, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+            *h = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+        Some(state)
+    }
+
+    fn padding(len: u64) -> Vec<u8> {
+        md_padding(len, Self::BLOCK_SIZE, true)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.feed(data, |block| compress(state, block));
+    }
+
+    fn finalize(mut self) -> Self::Digest {
+        let padding = Self::padding(self.buffer.len());
+        self.update(&padding);
+        let mut res = [0u8; 20];
+        for (chunk, h) in res.chunks_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&h.to_be_bytes());
+        }
+        res
+    }
+}
+
+pub fn sha1(msg: &[u8]) -> [u8; 20] {
+    Sha1::digest(msg)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::convert::to_hex;
+
+    use super::*;
+
+    #[test]
+    fn test_sha1() {
+        assert_eq!(
+            to_hex(&sha1(b"The quick brown fox jumps over the lazy dog")),
+            "2FD4E1C67A2D28FCED849EE1BB76E7391B93EB12"
+        );
+        assert_eq!(
+            to_hex(&sha1(b"The quick brown fox jumps over the lazy cog")),
+            "DE9F2C7FD25E1B3AFAD3E85A0BD17D9B100DB4B3"
+        );
+        assert_eq!(
+            to_hex(&sha1(b"")),
+            "DA39A3EE5E6B4B0D3255BFEF95601890AFD80709"
+        );
+    }
+}
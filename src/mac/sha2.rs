@@ -0,0 +1,372 @@
+use crate::mac::{md_padding, BlockBuffer, Hasher};
+
+pub const SHA224_INITIAL_STATE: [u32; 8] = [
+    0xC1059ED8, 0x367CD507, 0x3070DD17, 0xF70E5939, 0xFFC00B31, 0x68581511, 0x64F98FA7, 0xBEFA4FA4,
+];
+pub const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+pub const SHA384_INITIAL_STATE: [u64; 8] = [
+    0xCBBB9D5DC1059ED8,
+    0x629A292A367CD507,
+    0x9159015A3070DD17,
+    0x152FECD8F70E5939,
+    0x67332667FFC00B31,
+    0x8EB44A8768581511,
+    0xDB0C2E0D64F98FA7,
+    0x47B5481DBEFA4FA4,
+];
+pub const SHA512_INITIAL_STATE: [u64; 8] = [
+    0x6A09E667F3BCC908,
+    0xBB67AE8584CAA73B,
+    0x3C6EF372FE94F82B,
+    0xA54FF53A5F1D36F1,
+    0x510E527FADE682D1,
+    0x9B05688C2B3E6C1F,
+    0x1F83D9ABFB41BD6B,
+    0x5BE0CD19137E2179,
+];
+
+const K256: [u32; 64] = [
+    0x428A2F98, 0x71374491, 0xB5C0FBCF, 0xE9B5DBA5, 0x3956C25B, 0x59F111F1, 0x923F82A4, 0xAB1C5ED5,
+    0xD807AA98, 0x12835B01, 0x243185BE, 0x550C7DC3, 0x72BE5D74, 0x80DEB1FE, 0x9BDC06A7, 0xC19BF174,
+    0xE49B69C1, 0xEFBE4786, 0x0FC19DC6, 0x240CA1CC, 0x2DE92C6F, 0x4A7484AA, 0x5CB0A9DC, 0x76F988DA,
+    0x983E5152, 0xA831C66D, 0xB00327C8, 0xBF597FC7, 0xC6E00BF3, 0xD5A79147, 0x06CA6351, 0x14292967,
+    0x27B70A85, 0x2E1B2138, 0x4D2C6DFC, 0x53380D13, 0x650A7354, 0x766A0ABB, 0x81C2C92E, 0x92722C85,
+    0xA2BFE8A1, 0xA81A664B, 0xC24B8B70, 0xC76C51A3, 0xD192E819, 0xD6990624, 0xF40E3585, 0x106AA070,
+    0x19A4C116, 0x1E376C08, 0x2748774C, 0x34B0BCB5, 0x391C0CB3, 0x4ED8AA4A, 0x5B9CCA4F, 0x682E6FF3,
+    0x748F82EE, 0x78A5636F, 0x84C87814, 0x8CC70208, 0x90BEFFFA, 0xA4506CEB, 0xBEF9A3F7, 0xC67178F2,
+];
+
+const K512: [u64; 80] = [
+    0x428A2F98D728AE22,
+    0x7137449123EF65CD,
+    0xB5C0FBCFEC4D3B2F,
+    0xE9B5DBA58189DBBC,
+    0x3956C25BF348B538,
+    0x59F111F1B605D019,
+    0x923F82A4AF194F9B,
+    0xAB1C5ED5DA6D8118,
+    0xD807AA98A3030242,
+    0x12835B0145706FBE,
+    0x243185BE4EE4B28C,
+    0x550C7DC3D5FFB4E2,
+    0x72BE5D74F27B896F,
+    0x80DEB1FE3B1696B1,
+    0x9BDC06A725C71235,
+    0xC19BF174CF692694,
+    0xE49B69C19EF14AD2,
+    0xEFBE4786384F25E3,
+    0x0FC19DC68B8CD5B5,
+    0x240CA1CC77AC9C65,
+    0x2DE92C6F592B0275,
+    0x4A7484AA6EA6E483,
+    0x5CB0A9DCBD41FBD4,
+    0x76F988DA831153B5,
+    0x983E5152EE66DFAB,
+    0xA831C66D2DB43210,
+    0xB00327C898FB213F,
+    0xBF597FC7BEEF0EE4,
+    0xC6E00BF33DA88FC2,
+    0xD5A79147930AA725,
+    0x06CA6351E003826F,
+    0x142929670A0E6E70,
+    0x27B70A8546D22FFC,
+    0x2E1B21385C26C926,
+    0x4D2C6DFC5AC42AED,
+    0x53380D139D95B3DF,
+    0x650A73548BAF63DE,
+    0x766A0ABB3C77B2A8,
+    0x81C2C92E47EDAEE6,
+    0x92722C851482353B,
+    0xA2BFE8A14CF10364,
+    0xA81A664BBC423001,
+    0xC24B8B70D0F89791,
+    0xC76C51A30654BE30,
+    0xD192E819D6EF5218,
+    0xD69906245565A910,
+    0xF40E35855771202A,
+    0x106AA07032BBD1B8,
+    0x19A4C116B8D2D0C8,
+    0x1E376C085141AB53,
+    0x2748774CDF8EEB99,
+    0x34B0BCB5E19B48A8,
+    0x391C0CB3C5C95A63,
+    0x4ED8AA4AE3418ACB,
+    0x5B9CCA4F7763E373,
+    0x682E6FF3D6B2B8A3,
+    0x748F82EE5DEFB2FC,
+    0x78A5636F43172F60,
+    0x84C87814A1F0AB72,
+    0x8CC702081A6439EC,
+    0x90BEFFFA23631E28,
+    0xA4506CEBDE82BDE9,
+    0xBEF9A3F7B2C67915,
+    0xC67178F2E372532B,
+    0xCA273ECEEA26619C,
+    0xD186B8C721C0C207,
+    0xEADA7DD6CDE0EB1E,
+    0xF57D4F7FEE6ED178,
+    0x06F067AA72176FBA,
+    0x0A637DC5A2C898A6,
+    0x113F9804BEF90DAE,
+    0x1B710B35131C471B,
+    0x28DB77F523047D84,
+    0x32CAAB7B40C72493,
+    0x3C9EBE0A15C9BEBC,
+    0x431D67C49C100D4C,
+    0x4CC5D4BECB3E42B6,
+    0x597F299CFC657E2A,
+    0x5FCB6FAB3AD6FAEC,
+    0x6C44198C4A475817,
+];
+
+/// The SHA-256 compression function (also used by SHA-224): updates the state with a 64-byte
+/// block.
+pub fn compress256(state: &mut [u32; 8], block: &[u8]) {
+    assert_eq!(block.len(), 64);
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes(block[i * 4..(i + 1) * 4].try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ ((!e) & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K256[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (x, y) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *x = x.wrapping_add(y);
+    }
+}
+
+/// The SHA-512 compression function (also used by SHA-384): updates the state with a 128-byte
+/// block.
+pub fn compress512(state: &mut [u64; 8], block: &[u8]) {
+    assert_eq!(block.len(), 128);
+    let mut w = [0u64; 80];
+    for i in 0..16 {
+        w[i] = u64::from_be_bytes(block[i * 8..(i + 1) * 8].try_into().unwrap());
+    }
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ ((!e) & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K512[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (x, y) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *x = x.wrapping_add(y);
+    }
+}
+
+/// Defines a SHA-2 hasher. The digest is the big-endian state, truncated to the digest length.
+macro_rules! sha2 {
+    ($name:ident, $word:ty, $block_size:expr, $compress:ident, $iv:expr, $digest_len:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            state: [$word; 8],
+            buffer: BlockBuffer,
+        }
+
+        impl Hasher for $name {
+            const BLOCK_SIZE: usize = $block_size;
+            type State = [$word; 8];
+            type Digest = [u8; $digest_len];
+
+            fn new() -> Self {
+                Self::from_state($iv, 0).unwrap()
+            }
+
+            fn from_state(state: Self::State, processed_len: u64) -> Option<Self> {
+                Some(Self {
+                    state,
+                    buffer: BlockBuffer::new(Self::BLOCK_SIZE, processed_len)?,
+                })
+            }
+
+            /// Only the untruncated digests determine the state.
+            fn state_from_digest(digest: &Self::Digest) -> Option<Self::State> {
+                const WORD: usize = std::mem::size_of::<$word>();
+                if $digest_len != 8 * WORD {
+                    return None;
+                }
+                let mut state = [0; 8];
+                for (h, chunk) in state.iter_mut().zip(digest.chunks(WORD)) {
+                    *h = <$word>::from_be_bytes(chunk.try_into().unwrap());
+                }
+                Some(state)
+            }
+
+            fn padding(len: u64) -> Vec<u8> {
+                md_padding(len, Self::BLOCK_SIZE, true)
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                let state = &mut self.state;
+                self.buffer.feed(data, |block| $compress(state, block));
+            }
+
+            fn finalize(mut self) -> Self::Digest {
+                let padding = Self::padding(self.buffer.len());
+                self.update(&padding);
+                let bytes: Vec<u8> = self.state.iter().flat_map(|h| h.to_be_bytes()).collect();
+                bytes[..$digest_len].try_into().unwrap()
+            }
+        }
+    };
+}
+
+sha2!(Sha224, u32, 64, compress256, SHA224_INITIAL_STATE, 28);
+sha2!(Sha256, u32, 64, compress256, SHA256_INITIAL_STATE, 32);
+sha2!(Sha384, u64, 128, compress512, SHA384_INITIAL_STATE, 48);
+sha2!(Sha512, u64, 128, compress512, SHA512_INITIAL_STATE, 64);
+
+pub fn sha224(msg: &[u8]) -> [u8; 28] {
+    Sha224::digest(msg)
+}
+
+pub fn sha256(msg: &[u8]) -> [u8; 32] {
+    Sha256::digest(msg)
+}
+
+pub fn sha384(msg: &[u8]) -> [u8; 48] {
+    Sha384::digest(msg)
+}
+
+pub fn sha512(msg: &[u8]) -> [u8; 64] {
+    Sha512::digest(msg)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::convert::to_hex;
+
+    use super::*;
+
+    const TWO_BLOCKS: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            to_hex(&sha224(b"abc")),
+            "23097D223405D8228642A477BDA255B32AADBCE4BDA0B3F7E36C9DA7"
+        );
+        assert_eq!(
+            to_hex(&sha224(TWO_BLOCKS)),
+            "75388B16512776CC5DBA5DA1FD890150B0C6455CB4F58B1952522525"
+        );
+        assert_eq!(
+            to_hex(&sha224(b"")),
+            "D14A028C2A3A2BC9476102BB288234C415A2B01F828EA62AC5B3E42F"
+        );
+        assert_eq!(
+            to_hex(&sha256(b"abc")),
+            "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"
+        );
+        assert_eq!(
+            to_hex(&sha256(TWO_BLOCKS)),
+            "248D6A61D20638B8E5C026930C3E6039A33CE45964FF2167F6ECEDD419DB06C1"
+        );
+        assert_eq!(
+            to_hex(&sha256(b"")),
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855"
+        );
+    }
+
+    #[test]
+    fn test_sha512() {
+        assert_eq!(
+            to_hex(&sha384(b"abc")),
+            "CB00753F45A35E8BB5A03D699AC65007272C32AB0EDED1631A8B605A43FF5BED\
+             8086072BA1E7CC2358BAECA134C825A7"
+        );
+        assert_eq!(
+            to_hex(&sha384(TWO_BLOCKS)),
+            "3391FDDDFC8DC7393707A65B1B4709397CF8B1D162AF05ABFE8F450DE5F36BC6\
+             B0455A8520BC4E6F5FE95B1FE3C8452B"
+        );
+        assert_eq!(
+            to_hex(&sha384(b"")),
+            "38B060A751AC96384CD9327EB1B1E36A21FDB71114BE07434C0CC7BF63F6E1DA\
+             274EDEBFE76F65FBD51AD2F14898B95B"
+        );
+        assert_eq!(
+            to_hex(&sha512(b"abc")),
+            "DDAF35A193617ABACC417349AE20413112E6FA4E89A97EA20A9EEEE64B55D39A\
+             2192992A274FC1A836BA3C23A3FEEBBD454D4423643CE80E2A9AC94FA54CA49F"
+        );
+        assert_eq!(
+            to_hex(&sha512(TWO_BLOCKS)),
+            "204A8FC6DDA82F0A0CED7BEB8E08A41657C16EF468B228A8279BE331A703C335\
+             96FD15C13B1B07F9AA1D3BEA57789CA031AD85C7A71DD70354EC631238CA3445"
+        );
+        assert_eq!(
+            to_hex(&sha512(&[b'a'; 1000])),
+            "67BA5535A46E3F86DBFBED8CBBAF0125C76ED549FF8B0B9E03E0C88CF90FA634\
+             FA7B12B47D77B694DE488ACE8D9A65967DC96DF599727D3292A8D9D447709C97"
+        );
+    }
+
+    #[test]
+    fn state_from_digest_test() {
+        let digest = sha256(b"abc");
+        let state = Sha256::state_from_digest(&digest).unwrap();
+        let mut hasher = Sha256::from_state(state, 64).unwrap();
+        hasher.update(b"def");
+        let mut msg = b"abc".to_vec();
+        msg.extend(md_padding(3, 64, true));
+        msg.extend_from_slice(b"def");
+        assert_eq!(hasher.finalize(), sha256(&msg));
+        assert!(Sha256::from_state(state, 63).is_none());
+        assert!(Sha224::state_from_digest(&sha224(b"abc")).is_none());
+        assert!(Sha384::state_from_digest(&sha384(b"abc")).is_none());
+    }
+}
@@ -1,22 +1,167 @@
-#![allow(clippy::approx_constant)]
-#![allow(clippy::new_without_default)]
-#![allow(clippy::needless_range_loop)]
-
-pub mod ciphers;
-pub mod convert;
-pub mod freq;
-pub mod util;
-pub mod oracles;
-pub mod mersenne;
-pub mod mac;
-pub mod num;
-
-pub mod set1;
-pub mod set2;
-pub mod set3;
-pub mod set4;
-pub mod set5;
-
-fn main() {
-    set4::challenge32();
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    process::ExitCode,
+};
+
+use cryptopals::{
+    ciphers::{encrypt_aes_128_ctr, recover_fixed_nonce_keystream},
+    convert::from_base64,
+    crib::{printable, CribDragger},
+    freq::LogLikelihood,
+};
+
+const USAGE: &str = "usage: cryptopals crib <file> [--encrypt]
+
+Reads one base64 ciphertext per line, all encrypted with the same keystream.
+With --encrypt, the lines are plaintexts and are first encrypted under a random
+CTR key with a fixed nonce.";
+
+const HELP: &str = "commands:
+  show                        print the texts decrypted so far
+  try <text> <offset> <crib>  show the fragments given by the crib
+  drag <text> <crib>          rank the offsets of the crib in a text
+  lock <text> <offset> <crib> lock in the keystream given by the crib
+  unlock <offset> <len>       forget keystream bytes
+  auto [threshold]            lock in statistically recovered bytes (default 0.99)
+  help                        show this message
+  quit                        exit";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (path, encrypt) = match args.as_slice() {
+        [cmd, path] if cmd == "crib" => (path, false),
+        [cmd, path, flag] if cmd == "crib" && flag == "--encrypt" => (path, true),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let ciphers = match load(path, encrypt) {
+        Ok(ciphers) => ciphers,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut dragger = CribDragger::new(ciphers);
+    println!(
+        "{} texts. Type help for the commands.",
+        dragger.ciphers().len()
+    );
+    repl(&mut dragger);
+    ExitCode::SUCCESS
+}
+
+fn load(path: &str, encrypt: bool) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error>> {
+    let key: [u8; 16] = rand::random();
+    let mut res = vec![];
+    for line in fs::read_to_string(path)?.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let text = from_base64(line.trim().as_bytes())?;
+        res.push(if encrypt {
+            encrypt_aes_128_ctr(&text, &key, &[0; 8])?
+        } else {
+            text
+        });
+    }
+    Ok(res)
+}
+
+fn repl(dragger: &mut CribDragger) {
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        match run(dragger, line) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => println!("error: {}", e),
+        }
+    }
+}
+
+/// Splits off the first word of the line.
+fn next_word(line: &str) -> (&str, &str) {
+    let line = line.trim_start();
+    match line.find(' ') {
+        Some(i) => (&line[..i], &line[i + 1..]),
+        None => (line, ""),
+    }
+}
+
+fn next_number(line: &str) -> Result<(usize, &str), String> {
+    let (word, rest) = next_word(line);
+    let n = word
+        .parse()
+        .map_err(|_| format!("not a number: {:?}", word))?;
+    Ok((n, rest))
+}
+
+/// Runs one command. Returns false to quit.
+fn run(dragger: &mut CribDragger, line: &str) -> Result<bool, String> {
+    let (cmd, rest) = next_word(line);
+    match cmd {
+        "" => {}
+        "show" => {
+            for (i, text) in dragger.render().iter().enumerate() {
+                println!("{:3} {}", i, text);
+            }
+        }
+        "try" => {
+            let (index, rest) = next_number(rest)?;
+            let (offset, crib) = next_number(rest)?;
+            let fragments = dragger
+                .try_crib(index, offset, crib.as_bytes())
+                .map_err(|e| e.to_string())?;
+            for (i, fragment) in fragments.iter().enumerate() {
+                let text: String = fragment.iter().map(|&c| printable(c)).collect();
+                println!("{:3} {}", i, text);
+            }
+        }
+        "drag" => {
+            let (index, crib) = next_number(rest)?;
+            let ranking = dragger.drag(index, crib.as_bytes(), &LogLikelihood::english());
+            for &(offset, score) in ranking.iter().take(5) {
+                println!("offset {} (score {:.3}):", offset, score);
+                let fragments = dragger.try_crib(index, offset, crib.as_bytes()).unwrap();
+                for (i, fragment) in fragments.iter().enumerate() {
+                    let text: String = fragment.iter().map(|&c| printable(c)).collect();
+                    println!("  {:3} {}", i, text);
+                }
+            }
+        }
+        "lock" => {
+            let (index, rest) = next_number(rest)?;
+            let (offset, crib) = next_number(rest)?;
+            dragger
+                .lock(index, offset, crib.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+        "unlock" => {
+            let (offset, rest) = next_number(rest)?;
+            let (len, _) = next_number(rest)?;
+            dragger.unlock(offset, len);
+        }
+        "auto" => {
+            let threshold = match rest.trim() {
+                "" => 0.99,
+                t => t.parse().map_err(|_| format!("not a number: {:?}", t))?,
+            };
+            let recovered =
+                recover_fixed_nonce_keystream(dragger.ciphers(), &LogLikelihood::english());
+            dragger.lock_confident(&recovered, threshold);
+        }
+        "help" => println!("{}", HELP),
+        "quit" | "exit" => return Ok(false),
+        _ => return Err(format!("unknown command {:?}, type help", cmd)),
+    }
+    Ok(true)
 }
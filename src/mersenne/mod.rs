@@ -0,0 +1,618 @@
+pub mod search;
+pub mod solver;
+
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr},
+};
+
+use rand::{RngCore, SeedableRng};
+
+/// The word type of a Mersenne Twister: u32 or u64.
+pub trait Word:
+    Copy
+    + Eq
+    + Debug
+    + Default
+    + Send
+    + Sync
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_u64(x: u64) -> Self;
+    fn to_u64(self) -> u64;
+    fn wrapping_add(self, other: Self) -> Self;
+    fn wrapping_sub(self, other: Self) -> Self;
+    fn wrapping_mul(self, other: Self) -> Self;
+}
+
+macro_rules! word {
+    ($t:ty) => {
+        impl Word for $t {
+            const BITS: u32 = <$t>::BITS;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn from_u64(x: u64) -> Self {
+                x as $t
+            }
+
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+
+            fn wrapping_add(self, other: Self) -> Self {
+                <$t>::wrapping_add(self, other)
+            }
+
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$t>::wrapping_sub(self, other)
+            }
+
+            fn wrapping_mul(self, other: Self) -> Self {
+                <$t>::wrapping_mul(self, other)
+            }
+        }
+    };
+}
+
+word!(u32);
+word!(u64);
+
+/// The constant set of a Mersenne Twister, named as in the reference implementations.
+pub trait MtParams {
+    type Word: Word;
+    /// Degree of recurrence: the number of words of state.
+    const N: usize;
+    /// Middle word offset.
+    const M: usize;
+    /// Separation point of a word: the lower R bits go to the lower mask.
+    const R: u32;
+    /// Coefficients of the twist matrix.
+    const A: Self::Word;
+    const U: u32;
+    const D: Self::Word;
+    const S: u32;
+    const B: Self::Word;
+    const T: u32;
+    const C: Self::Word;
+    const L: u32;
+    /// Multiplier of the seeding recurrence.
+    const F: Self::Word;
+    /// Multipliers of the two passes of init_by_array.
+    const INIT_MUL1: Self::Word;
+    const INIT_MUL2: Self::Word;
+}
+
+/// The constants of the 32-bit MT19937.
+pub struct Mt32;
+
+impl MtParams for Mt32 {
+    type Word = u32;
+    const N: usize = 624;
+    const M: usize = 397;
+    const R: u32 = 31;
+    const A: u32 = 0x9908B0DF;
+    const U: u32 = 11;
+    const D: u32 = 0xFFFFFFFF;
+    const S: u32 = 7;
+    const B: u32 = 0x9D2C5680;
+    const T: u32 = 15;
+    const C: u32 = 0xEFC60000;
+    const L: u32 = 18;
+    const F: u32 = 1812433253;
+    const INIT_MUL1: u32 = 1664525;
+    const INIT_MUL2: u32 = 1566083941;
+}
+
+/// The constants of MT19937-64.
+pub struct Mt64;
+
+impl MtParams for Mt64 {
+    type Word = u64;
+    const N: usize = 312;
+    const M: usize = 156;
+    const R: u32 = 31;
+    const A: u64 = 0xB5026F5AA96619E9;
+    const U: u32 = 29;
+    const D: u64 = 0x5555555555555555;
+    const S: u32 = 17;
+    const B: u64 = 0x71D67FFFEDA60000;
+    const T: u32 = 37;
+    const C: u64 = 0xFFF7EEE000000000;
+    const L: u32 = 43;
+    const F: u64 = 6364136223846793005;
+    const INIT_MUL1: u64 = 3935559000370003845;
+    const INIT_MUL2: u64 = 2862933555777941757;
+}
+
+/// A Mersenne Twister with the given constant set.
+pub struct MersenneTwister<P: MtParams> {
+    state: Vec<P::Word>,
+    index: usize,
+    params: PhantomData<P>,
+}
+
+pub type MT19937 = MersenneTwister<Mt32>;
+pub type MT19937_64 = MersenneTwister<Mt64>;
+
+impl<P: MtParams> Clone for MersenneTwister<P> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            index: self.index,
+            params: PhantomData,
+        }
+    }
+}
+
+impl<P: MtParams> Iterator for MersenneTwister<P> {
+    type Item = P::Word;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= P::N {
+            self.twist();
+        }
+        let y = Self::temper(self.state[self.index]);
+        self.index += 1;
+        Some(y)
+    }
+}
+
+impl<P: MtParams> MersenneTwister<P> {
+    /// Seeds the generator like init_genrand in the reference implementation.
+    pub fn new(seed: P::Word) -> Self {
+        Self::from_state(&Self::seed_state(seed))
+    }
+
+    fn seed_state(seed: P::Word) -> Vec<P::Word> {
+        let mut state = vec![seed; P::N];
+        for i in 1..P::N {
+            state[i] = (state[i - 1] ^ (state[i - 1] >> (P::Word::BITS - 2)))
+                .wrapping_mul(P::F)
+                .wrapping_add(P::Word::from_u64(i as u64));
+        }
+        state
+    }
+
+    /// Seeds the generator with an array of words like init_by_array in the reference
+    /// implementation. CPython's random.seed and NumPy's RandomState seed this way, from the
+    /// 32-bit words of the seed.
+    pub fn from_array(key: &[P::Word]) -> Self {
+        let n = P::N;
+        let mut state = Self::seed_state(P::Word::from_u64(19650218));
+        let prev =
+            |state: &[P::Word], i: usize| state[i - 1] ^ (state[i - 1] >> (P::Word::BITS - 2));
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..n.max(key.len()) {
+            state[i] = (state[i] ^ prev(&state, i).wrapping_mul(P::INIT_MUL1))
+                .wrapping_add(key.get(j).copied().unwrap_or(P::Word::ZERO))
+                .wrapping_add(P::Word::from_u64(j as u64));
+            i += 1;
+            j += 1;
+            if i >= n {
+                state[0] = state[n - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..n - 1 {
+            state[i] = (state[i] ^ prev(&state, i).wrapping_mul(P::INIT_MUL2))
+                .wrapping_sub(P::Word::from_u64(i as u64));
+            i += 1;
+            if i >= n {
+                state[0] = state[n - 1];
+                i = 1;
+            }
+        }
+        state[0] = P::Word::ONE << (P::Word::BITS - 1);
+        Self::from_state(&state)
+    }
+
+    /// A generator that twists the given state before its first output, as after seeding.
+    /// Panics unless the state has N words.
+    pub fn from_state(state: &[P::Word]) -> Self {
+        Self::from_state_at(state, P::N)
+    }
+
+    /// A generator with a twisted state, of which index words have already been output: the
+    /// next output is the tempered state[index]. Panics unless the state has N words and
+    /// index <= N.
+    pub fn from_state_at(state: &[P::Word], index: usize) -> Self {
+        assert_eq!(state.len(), P::N, "the state must have {} words", P::N);
+        assert!(index <= P::N, "index {} is past the state", index);
+        Self {
+            state: state.to_vec(),
+            index,
+            params: PhantomData,
+        }
+    }
+
+    /// Clones a generator from N consecutive outputs, taken right after a twist (e.g. the first N
+    /// outputs after seeding). The clone continues where the outputs end.
+    pub fn clone_from_outputs(outputs: &[P::Word]) -> Self {
+        let state: Vec<_> = outputs.iter().map(|&y| Self::untemper(y)).collect();
+        Self::from_state(&state)
+    }
+
+    fn twist(&mut self) {
+        let lower_mask = !(!P::Word::ZERO << P::R);
+        let upper_mask = !lower_mask;
+        for i in 0..P::N {
+            let x = (self.state[i] & upper_mask) | (self.state[(i + 1) % P::N] & lower_mask);
+            let mut x_a = x >> 1;
+            if x & P::Word::ONE != P::Word::ZERO {
+                x_a = x_a ^ P::A;
+            }
+            self.state[i] = self.state[(i + P::M) % P::N] ^ x_a;
+        }
+        self.index = 0
+    }
+
+    /// Undoes a twist, recovering the state from before it.
+    fn untwist(&mut self) {
+        let lower_mask = !(!P::Word::ZERO << P::R);
+        let upper_mask = !lower_mask;
+        let top_bit = P::Word::ONE << (P::Word::BITS - 1);
+        // The word (upper(x[i]) | lower(x[i + 1])) that the twist made word i from. As A has its
+        // top bit set, whether it was odd shows in the top bit of its image.
+        let recover = |state: &[P::Word], i: usize| {
+            let mut x_a = state[i] ^ state[(i + P::M) % P::N];
+            let odd = x_a & top_bit != P::Word::ZERO;
+            if odd {
+                x_a = x_a ^ P::A;
+            }
+            (x_a << 1) | if odd { P::Word::ONE } else { P::Word::ZERO }
+        };
+        // Going down, the words after i are already restored, the words before it are not, just
+        // as the twist found them.
+        for i in (0..P::N).rev() {
+            let upper = recover(&self.state, i) & upper_mask;
+            let lower = recover(&self.state, (i + P::N - 1) % P::N) & lower_mask;
+            self.state[i] = upper | lower;
+        }
+        self.index = P::N;
+    }
+
+    /// Steps back one output: returns the output last returned by next, which next will return
+    /// again.
+    /// Going back past the first output after seeding gives words the seeding never produced.
+    pub fn prev(&mut self) -> P::Word {
+        if self.index == 0 {
+            self.untwist();
+        }
+        self.index -= 1;
+        Self::temper(self.state[self.index])
+    }
+
+    /// Steps back n outputs.
+    pub fn rewind(&mut self, mut n: u64) {
+        while n > 0 {
+            if self.index == 0 {
+                self.untwist();
+            }
+            let step = n.min(self.index as u64);
+            self.index -= step as usize;
+            n -= step;
+        }
+    }
+
+    /// Steps forward n outputs, twisting the state as needed without tempering any output.
+    pub fn advance(&mut self, mut n: u64) {
+        while n > 0 {
+            if self.index >= P::N {
+                self.twist();
+            }
+            let step = n.min((P::N - self.index) as u64);
+            self.index += step as usize;
+            n -= step;
+        }
+    }
+
+    /// The seed that gives a state from new, if there is one.
+    /// The first word of a state untwisted back to seeding is only right in its top bit, so
+    /// the seed is found by inverting the seeding recurrence from the second word.
+    fn seed_of(state: &[P::Word]) -> Option<P::Word> {
+        // The inverse of F modulo 2^BITS, by Newton's iteration.
+        let two = P::Word::ONE.wrapping_add(P::Word::ONE);
+        let mut f_inv = P::F;
+        for _ in 0..6 {
+            f_inv = f_inv.wrapping_mul(two.wrapping_sub(P::F.wrapping_mul(f_inv)));
+        }
+        let y = state[1].wrapping_sub(P::Word::ONE).wrapping_mul(f_inv);
+        let seed = inv_xor_rsh(y, P::Word::BITS - 2, !P::Word::ZERO);
+        (Self::seed_state(seed)[1..] == state[1..]).then_some(seed)
+    }
+
+    /// Recovers the seed given to new, and the number of outputs since seeding, by rewinding at
+    /// most max_outputs outputs. Generators seeded with from_array are not found.
+    pub fn recover_seed(&self, max_outputs: u64) -> Option<(P::Word, u64)> {
+        let mut rng = self.clone();
+        let mut outputs = 0;
+        loop {
+            if rng.index == P::N {
+                if let Some(seed) = Self::seed_of(&rng.state) {
+                    return Some((seed, outputs));
+                }
+            }
+            outputs += rng.index as u64;
+            if outputs > max_outputs {
+                return None;
+            }
+            rng.untwist();
+        }
+    }
+
+    pub fn temper(mut y: P::Word) -> P::Word {
+        y = y ^ ((y >> P::U) & P::D);
+        y = y ^ ((y << P::S) & P::B);
+        y = y ^ ((y << P::T) & P::C);
+        y = y ^ (y >> P::L);
+        y
+    }
+
+    pub fn untemper(mut y: P::Word) -> P::Word {
+        let all = !P::Word::ZERO;
+        y = inv_xor_rsh(y, P::L, all);
+        y = inv_xor_lsh(y, P::T, P::C);
+        y = inv_xor_lsh(y, P::S, P::B);
+        y = inv_xor_rsh(y, P::U, P::D);
+        y
+    }
+}
+
+/// Outputs in order. next_u64 takes two outputs, the first as the low half, and fill_bytes
+/// takes the outputs as little-endian bytes, as in rand_core's helpers.
+impl RngCore for MT19937 {
+    fn next_u32(&mut self) -> u32 {
+        self.next().unwrap()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        let high = self.next_u32() as u64;
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// The seed is the little-endian seed of new.
+impl SeedableRng for MT19937 {
+    type Seed = [u8; 4];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u32::from_le_bytes(seed))
+    }
+
+    /// Seeds with the low 32 bits of the state, unlike the default which hashes it.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state as u32)
+    }
+}
+
+/// Outputs in order. next_u32 takes the low half of an output, and fill_bytes takes the
+/// outputs as little-endian bytes, as in rand_core's helpers.
+impl RngCore for MT19937_64 {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// The seed is the little-endian seed of new.
+impl SeedableRng for MT19937_64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    /// Seeds with the state itself, unlike the default which hashes it.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+fn inv_xor_lsh<W: Word>(mut x: W, sh: u32, mask: W) -> W {
+    let mut res = W::ZERO;
+    while x != W::ZERO {
+        res = res ^ x;
+        x = (x << sh) & mask;
+    }
+    res
+}
+
+fn inv_xor_rsh<W: Word>(mut x: W, sh: u32, mask: W) -> W {
+    let mut res = W::ZERO;
+    while x != W::ZERO {
+        res = res ^ x;
+        x = (x >> sh) & mask;
+    }
+    res
+}
+
+/// Inverts the tempering of MT19937.
+pub fn untemper(y: u32) -> u32 {
+    MT19937::untemper(y)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rand::Rng;
+
+    use crate::ciphers::mt19937_stream_cipher;
+
+    use super::*;
+
+    #[test]
+    fn temper_test() {
+        let x = 0xDEADBEEF;
+        assert_eq!(untemper(MT19937::temper(x)), x);
+        assert_eq!(MT19937::temper(untemper(x)), x);
+        let x = 0xDEADBEEF_CAFEBABE;
+        assert_eq!(MT19937_64::untemper(MT19937_64::temper(x)), x);
+        assert_eq!(MT19937_64::temper(MT19937_64::untemper(x)), x);
+    }
+
+    #[test]
+    fn reference_test() {
+        // Outputs of mt19937ar.c and mt19937-64.c.
+        let mut rng = MT19937::new(5489);
+        assert_eq!(rng.nth(9999), Some(4123659995));
+        let rng = MT19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+        assert_eq!(
+            rng.take(5).collect::<Vec<_>>(),
+            [1067595299, 955945823, 477289528, 4107218783, 4228976476]
+        );
+        let mut rng = MT19937_64::new(5489);
+        assert_eq!(rng.nth(9999), Some(9981545732273789042));
+        let rng = MT19937_64::from_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        assert_eq!(
+            rng.take(3).collect::<Vec<_>>(),
+            [
+                7266447313870364031,
+                4946485549665804864,
+                16945909448695747420
+            ]
+        );
+    }
+
+    #[test]
+    fn cpython_seed_test() {
+        // random.seed(1234); [random.getrandbits(32) for _ in range(3)]
+        let rng = MT19937::from_array(&[1234]);
+        assert_eq!(
+            rng.take(3).collect::<Vec<_>>(),
+            [4150886329, 3342196574, 1892932127]
+        );
+    }
+
+    #[test]
+    fn rewind_test() {
+        let mut rng = MT19937::new(1234);
+        let outputs: Vec<_> = rng.by_ref().take(2000).collect();
+        for &y in outputs[1000..].iter().rev() {
+            assert_eq!(rng.prev(), y);
+        }
+        rng.rewind(900);
+        assert_eq!(rng.next(), Some(outputs[100]));
+        rng.rewind(101);
+        assert_eq!(rng.by_ref().take(2000).collect::<Vec<_>>(), outputs);
+
+        let mut rng = MT19937_64::new(1234);
+        let outputs: Vec<_> = rng.by_ref().take(1000).collect();
+        rng.rewind(1000);
+        assert_eq!(rng.take(1000).collect::<Vec<_>>(), outputs);
+    }
+
+    #[test]
+    fn advance_test() {
+        let outputs: Vec<_> = MT19937::new(1234).take(3000).collect();
+        for (start, n) in [(0, 0), (0, 624), (10, 614), (100, 2000), (623, 1)] {
+            let mut rng = MT19937::new(1234);
+            rng.nth(start);
+            rng.advance(n);
+            assert_eq!(rng.next(), Some(outputs[start + 1 + n as usize]));
+        }
+    }
+
+    #[test]
+    fn recover_seed_test() {
+        for skip in [0, 1, 623, 624, 625, 5000] {
+            let mut rng = MT19937::new(0xC0FFEE);
+            for _ in 0..skip {
+                rng.next();
+            }
+            assert_eq!(rng.recover_seed(10000), Some((0xC0FFEE, skip)));
+            if skip > 0 {
+                assert_eq!(rng.recover_seed(skip - 1), None);
+            }
+        }
+        let mut rng = MT19937_64::new(u64::MAX - 1);
+        rng.nth(999);
+        assert_eq!(rng.recover_seed(1000), Some((u64::MAX - 1, 1000)));
+        assert_eq!(MT19937::from_array(&[1]).recover_seed(10000), None);
+    }
+
+    #[test]
+    fn rng_core_test() {
+        let mut rng = MT19937::from_seed(1234u32.to_le_bytes());
+        let outputs: Vec<_> = MT19937::new(1234).take(5).collect();
+        assert_eq!(rng.next_u32(), outputs[0]);
+        assert_eq!(
+            rng.next_u64(),
+            outputs[1] as u64 | (outputs[2] as u64) << 32
+        );
+        let mut bytes = [0; 7];
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes[..4], outputs[3].to_le_bytes());
+        assert_eq!(bytes[4..], outputs[4].to_le_bytes()[..3]);
+
+        let mut bytes = [0; 21];
+        MT19937::seed_from_u64(1234).fill_bytes(&mut bytes);
+        assert_eq!(bytes.to_vec(), mt19937_stream_cipher(&[0; 21], 1234));
+
+        let mut rng = MT19937_64::seed_from_u64(5489);
+        let outputs: Vec<_> = MT19937_64::new(5489).take(3).collect();
+        assert_eq!(rng.next_u64(), outputs[0]);
+        assert_eq!(rng.next_u32(), outputs[1] as u32);
+        let mut bytes = [0; 5];
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes, outputs[2].to_le_bytes()[..5]);
+
+        // Usable wherever a Rng is, and cloneable.
+        let mut rng = MT19937::seed_from_u64(42);
+        let mut cloned = rng.clone();
+        let x: f64 = rng.gen();
+        assert_eq!(cloned.gen::<f64>(), x);
+        assert!((10..20).contains(&rng.gen_range(10..20)));
+    }
+
+    #[test]
+    fn clone_test() {
+        let mut rng = MT19937_64::from_array(&[42]);
+        let outputs: Vec<_> = rng.by_ref().take(312).collect();
+        let mut cloned = MT19937_64::clone_from_outputs(&outputs);
+        for _ in 0..1000 {
+            assert_eq!(rng.next(), cloned.next());
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    ops::RangeInclusive,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use crate::mersenne::MT19937;
+
+/// A brute-force search for the seed of an MT19937, split across threads, that stops as soon
+/// as the smallest matching seed is known.
+#[derive(Clone, Debug)]
+pub struct SeedSearch {
+    seeds: RangeInclusive<u32>,
+    threads: usize,
+}
+
+impl SeedSearch {
+    /// Searches the given seeds, with as many threads as the machine runs in parallel.
+    pub fn new(seeds: RangeInclusive<u32>) -> Self {
+        Self {
+            seeds,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    /// All 16-bit seeds.
+    pub fn u16() -> Self {
+        Self::new(0..=u16::MAX as u32)
+    }
+
+    /// All 32-bit seeds. This takes hours.
+    pub fn u32() -> Self {
+        Self::new(0..=u32::MAX)
+    }
+
+    /// The Unix timestamps of the last window seconds up to now. Timestamps past u32::MAX are
+    /// not seeds, so they are left out.
+    pub fn time_window(now: u64, window: u64) -> Self {
+        let end = now.min(u32::MAX as u64) as u32;
+        match u32::try_from(now.saturating_sub(window)) {
+            Ok(start) => Self::new(start..=end),
+            // The whole window is past the seeds.
+            Err(_) => Self::new(RangeInclusive::new(1, 0)),
+        }
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Returns the smallest seed for which check holds, if any. Thread t tries the seeds
+    /// congruent to t modulo the number of threads in increasing order, and stops at its first
+    /// match or once it passes the smallest match found so far.
+    pub fn find(&self, check: impl Fn(u32) -> bool + Sync) -> Option<u32> {
+        let (start, end) = (*self.seeds.start() as u64, *self.seeds.end() as u64);
+        // Wider than the seeds, so that no seed is mistaken for "none found".
+        let best = AtomicU64::new(u64::MAX);
+        let (check, best) = (&check, &best);
+        thread::scope(|s| {
+            for t in 0..self.threads as u64 {
+                s.spawn(move || {
+                    for seed in (start + t..=end).step_by(self.threads) {
+                        if seed > best.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        if check(seed as u32) {
+                            best.fetch_min(seed, Ordering::Relaxed);
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        u32::try_from(best.load(Ordering::Relaxed)).ok()
+    }
+
+    /// Finds the seed of a generator whose outputs, after skipping some, start with the given
+    /// ones.
+    pub fn find_outputs(&self, skip: usize, outputs: &[u32]) -> Option<u32> {
+        self.find(|seed| {
+            MT19937::new(seed)
+                .skip(skip)
+                .zip(outputs)
+                .all(|(y, &x)| y == x)
+        })
+    }
+
+    /// Finds the key of a ciphertext from mt19937_stream_cipher, given the plaintext known at
+    /// some offset.
+    pub fn find_stream_key(&self, cipher: &[u8], offset: usize, known: &[u8]) -> Option<u32> {
+        assert!(
+            offset + known.len() <= cipher.len(),
+            "the known plaintext goes past the ciphertext"
+        );
+        let keystream: Vec<u8> = cipher[offset..]
+            .iter()
+            .zip(known)
+            .map(|(&c, &p)| c ^ p)
+            .collect();
+        self.find(|seed| {
+            let bytes = MT19937::new(seed).flat_map(u32::to_le_bytes);
+            bytes.skip(offset).zip(&keystream).all(|(k, &x)| k == x)
+        })
+    }
+}
+
+/// A password reset token: the first len bytes of the keystream of an MT19937 seeded with a
+/// timestamp.
+pub fn password_reset_token(timestamp: u32, len: usize) -> Vec<u8> {
+    MT19937::new(timestamp)
+        .flat_map(u32::to_le_bytes)
+        .take(len)
+        .collect()
+}
+
+/// Checks whether a token came from password_reset_token with a timestamp in the last window
+/// seconds up to now, and returns the timestamp if so.
+pub fn token_timestamp(token: &[u8], now: u64, window: u64) -> Option<u32> {
+    SeedSearch::time_window(now, window).find_stream_key(token, 0, &vec![0; token.len()])
+}
+
+#[cfg(test)]
+pub mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::ciphers::mt19937_stream_cipher;
+
+    use super::*;
+
+    #[test]
+    fn seed_search_test() {
+        let outputs: Vec<_> = MT19937::new(40000).skip(3).take(2).collect();
+        for threads in [1, 3] {
+            let search = SeedSearch::new(39000..=41000).threads(threads);
+            assert_eq!(search.find_outputs(3, &outputs), Some(40000));
+            assert_eq!(search.find_outputs(2, &outputs), None);
+        }
+        for threads in [1, 2, 8] {
+            let search = SeedSearch::new(100..=200).threads(threads);
+            assert_eq!(search.find(|seed| seed % 7 == 0), Some(105));
+            assert_eq!(search.find(|seed| seed % 40 == 3), Some(123));
+            assert_eq!(search.find(|seed| seed > 150), Some(151));
+        }
+        let last = SeedSearch::new(u32::MAX - 10..=u32::MAX).threads(8);
+        assert_eq!(last.find(|seed| seed == u32::MAX), Some(u32::MAX));
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = SeedSearch::new(1..=0);
+        assert_eq!(empty.find(|_| true), None);
+
+        let max = u32::MAX as u64;
+        assert_eq!(SeedSearch::time_window(100, 10).seeds, 90..=100);
+        assert_eq!(
+            SeedSearch::time_window(max + 5, 10).seeds,
+            max as u32 - 5..=u32::MAX
+        );
+        assert_eq!(SeedSearch::time_window(max + 20, 10).find(|_| true), None);
+
+        let cipher = mt19937_stream_cipher(b"xxxxxxxxxxknown plaintext", 1234);
+        let search = SeedSearch::u16();
+        assert_eq!(
+            search.find_stream_key(&cipher, 10, b"known plaintext"),
+            Some(1234)
+        );
+    }
+
+    #[test]
+    fn token_test() {
+        let now = 1_700_000_000;
+        let token = password_reset_token(now as u32 - 300, 16);
+        assert_eq!(token_timestamp(&token, now, 3600), Some(now as u32 - 300));
+        assert_eq!(token_timestamp(&token, now, 60), None);
+        let random_token: [u8; 16] = ChaCha8Rng::seed_from_u64(12345).gen();
+        assert_eq!(token_timestamp(&random_token, now, 3600), None);
+    }
+}
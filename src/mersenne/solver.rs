@@ -0,0 +1,341 @@
+use crate::{
+    error::{Error, Result},
+    mersenne::{Mt32, MtParams, MT19937},
+};
+
+const N: usize = Mt32::N;
+const M: usize = Mt32::M;
+/// Number of unknowns: the bits of N consecutive state words.
+const VARS: usize = N * 32;
+/// Number of u64 words in a row.
+const ROW_LEN: usize = VARS / 64;
+/// The unknown bits that only the first output depends on: the lower bits of the first word
+/// never enter the twist.
+const FIRST_WORD_LOW_BITS: usize = Mt32::R as usize;
+/// The dimension of the state space.
+pub const STATE_BITS: usize = VARS - FIRST_WORD_LOW_BITS;
+/// The most outputs the observations may span, first to last. The solver twists a symbolic
+/// state through every output in between, which takes about a second per 50 twists of the
+/// state in a release build.
+pub const MAX_SPAN: u64 = 64 * N as u64;
+
+/// Known bits of one output of MT19937.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Observation {
+    /// Position of the output in the stream, from any origin.
+    pub position: u64,
+    /// The known bits.
+    pub mask: u32,
+    /// The output, of which only the bits in mask are used.
+    pub value: u32,
+}
+
+impl Observation {
+    pub fn full(position: u64, value: u32) -> Self {
+        Self {
+            position,
+            mask: !0,
+            value,
+        }
+    }
+
+    /// The top bits of an output, given as a number of that many bits, as returned by e.g.
+    /// Python's getrandbits(bits).
+    pub fn top_bits(position: u64, value: u32, bits: u32) -> Self {
+        assert!((1..=32).contains(&bits), "bits must be between 1 and 32");
+        Self {
+            position,
+            mask: !0 << (32 - bits),
+            value: value << (32 - bits),
+        }
+    }
+
+    /// The bits given away by output % n: only the low bits, as many as the factors of 2 of n.
+    pub fn modulo(position: u64, value: u32, n: u32) -> Self {
+        assert_ne!(n, 0, "the modulus must not be 0");
+        Self {
+            position,
+            mask: (1 << n.trailing_zeros()) - 1,
+            value,
+        }
+    }
+
+    /// The observations given by a float from Python's random.random(), which is made of the
+    /// top 27 and 26 bits of two consecutive outputs.
+    pub fn python_random(position: u64, x: f64) -> [Self; 2] {
+        let n = (x * (1u64 << 53) as f64) as u64;
+        [
+            Self::top_bits(position, (n >> 26) as u32, 27),
+            Self::top_bits(position + 1, (n & ((1 << 26) - 1)) as u32, 26),
+        ]
+    }
+}
+
+/// The rows giving each bit of the output as a sum of bits of the state word.
+fn temper_rows() -> [u32; 32] {
+    let mut rows = [0; 32];
+    for i in 0..32 {
+        let column = MT19937::temper(1 << i);
+        for (j, row) in rows.iter_mut().enumerate() {
+            *row |= ((column >> j) & 1) << i;
+        }
+    }
+    rows
+}
+
+/// Linear equations over GF(2) in echelon form: pivots[c] has its lowest set bit at c.
+struct System {
+    pivots: Vec<Option<(Vec<u64>, bool)>>,
+    rank: usize,
+}
+
+impl System {
+    fn new() -> Self {
+        Self {
+            pivots: vec![None; VARS],
+            rank: 0,
+        }
+    }
+
+    fn add(&mut self, mut row: Vec<u64>, mut rhs: bool) -> Result<()> {
+        let mut start = 0;
+        loop {
+            while start < ROW_LEN && row[start] == 0 {
+                start += 1;
+            }
+            if start == ROW_LEN {
+                return if rhs {
+                    Err(Error::InconsistentObservations)
+                } else {
+                    Ok(())
+                };
+            }
+            let c = start * 64 + row[start].trailing_zeros() as usize;
+            match &self.pivots[c] {
+                Some((pivot, pivot_rhs)) => {
+                    for (x, &y) in row[start..].iter_mut().zip(&pivot[start..]) {
+                        *x ^= y;
+                    }
+                    rhs ^= pivot_rhs;
+                }
+                None => {
+                    self.pivots[c] = Some((row, rhs));
+                    self.rank += 1;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Solves by back substitution, with the free unknowns set to 0.
+    fn solve(&self) -> Vec<u64> {
+        let mut res = vec![0u64; ROW_LEN];
+        for c in (0..VARS).rev() {
+            if let Some((row, rhs)) = &self.pivots[c] {
+                let mut bit = *rhs;
+                for (x, &y) in row.iter().zip(&res).skip(c / 64) {
+                    bit ^= (x & y).count_ones() % 2 == 1;
+                }
+                if bit {
+                    res[c / 64] |= 1 << (c % 64);
+                }
+            }
+        }
+        res
+    }
+
+    /// Whether every unknown the future outputs depend on is determined.
+    fn determined(&self) -> bool {
+        self.pivots
+            .iter()
+            .enumerate()
+            .all(|(c, p)| p.is_some() || c < FIRST_WORD_LOW_BITS)
+    }
+}
+
+/// The state words as sums of the unknowns, twisted in place like the generator's state.
+/// Each word is 32 rows, one per bit.
+struct SymbolicState {
+    rows: Vec<u64>,
+}
+
+impl SymbolicState {
+    fn new() -> Self {
+        let mut rows = vec![0u64; N * 32 * ROW_LEN];
+        for v in 0..VARS {
+            rows[v * ROW_LEN + v / 64] = 1 << (v % 64);
+        }
+        Self { rows }
+    }
+
+    fn row(&self, word: usize, bit: usize) -> &[u64] {
+        let start = (word * 32 + bit) * ROW_LEN;
+        &self.rows[start..start + ROW_LEN]
+    }
+
+    /// Replaces word i, which is x[k], with x[k + N] = x[k + M] ^ twist(x[k], x[k + 1]).
+    fn twist_word(&mut self, i: usize) {
+        let next = (i + 1) % N;
+        let mut new = vec![0u64; 32 * ROW_LEN];
+        for (j, bits) in new.chunks_mut(ROW_LEN).enumerate() {
+            bits.copy_from_slice(self.row((i + M) % N, j));
+            // Bit j of (upper(x[k]) | lower(x[k + 1])) >> 1.
+            let shifted = match j {
+                0..=29 => Some(self.row(next, j + 1)),
+                30 => Some(self.row(i, 31)),
+                _ => None,
+            };
+            for src in shifted
+                .into_iter()
+                .chain(((Mt32::A >> j) & 1 == 1).then_some(self.row(next, 0)))
+            {
+                for (x, &y) in bits.iter_mut().zip(src) {
+                    *x ^= y;
+                }
+            }
+        }
+        let start = i * 32 * ROW_LEN;
+        self.rows[start..start + 32 * ROW_LEN].copy_from_slice(&new);
+    }
+}
+
+/// Recovers the state of an MT19937 from partial observations of its outputs, which need not
+/// be consecutive nor start at a twist, by solving the linear system over GF(2) relating the
+/// observed bits to the state bits.
+/// The observations must give at least STATE_BITS independent bits, so with missing outputs or
+/// few bits per output they have to span more than 624 outputs.
+/// Returns a clone of the generator positioned right after the last observation.
+///
+/// The system is dense, and takes about 100 MB while solving. Observations spanning more than
+/// MAX_SPAN outputs are rejected with Error::ObservationSpanTooLong.
+pub fn recover_state(observations: &[Observation]) -> Result<MT19937> {
+    let mut observations = observations.to_vec();
+    observations.sort_by_key(|o| o.position);
+    let (first, last) = match (observations.first(), observations.last()) {
+        (Some(first), Some(last)) => (first.position, last.position),
+        _ => return Err(Error::Underdetermined(0)),
+    };
+    let span = (last - first).saturating_add(1);
+    if span > MAX_SPAN {
+        return Err(Error::ObservationSpanTooLong(span));
+    }
+
+    let temper = temper_rows();
+    let mut system = System::new();
+    let mut symbolic = SymbolicState::new();
+    // Symbolic words are known up to position first + twisted.
+    let mut twisted = N as u64;
+    for o in &observations {
+        let offset = o.position - first;
+        while offset >= twisted {
+            symbolic.twist_word((twisted % N as u64) as usize);
+            twisted += 1;
+        }
+        let word = (offset % N as u64) as usize;
+        for (j, &temper_row) in temper.iter().enumerate() {
+            if (o.mask >> j) & 1 == 0 {
+                continue;
+            }
+            let mut row = vec![0u64; ROW_LEN];
+            for i in (0..32).filter(|i| (temper_row >> i) & 1 == 1) {
+                for (x, &y) in row.iter_mut().zip(symbolic.row(word, i)) {
+                    *x ^= y;
+                }
+            }
+            system.add(row, (o.value >> j) & 1 == 1)?;
+        }
+    }
+    if !system.determined() {
+        return Err(Error::Underdetermined(system.rank));
+    }
+
+    let solution = system.solve();
+    let state: Vec<u32> = (0..N)
+        .map(|w| (solution[w / 2] >> (32 * (w % 2))) as u32)
+        .collect();
+    let mut rng = MT19937::from_state_at(&state, 0);
+    rng.advance(span);
+    Ok(rng)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn full_outputs_test() {
+        let mut rng = MT19937::new(1234);
+        // Not aligned with the twist, and with gaps.
+        rng.nth(100);
+        let observations: Vec<_> = (0..1400)
+            .map(|i| Observation::full(i, rng.next().unwrap()))
+            .filter(|o| o.position % 10 != 3)
+            .collect();
+        let mut cloned = recover_state(&observations).unwrap();
+        for _ in 0..1000 {
+            assert_eq!(rng.next(), cloned.next());
+        }
+    }
+
+    #[test]
+    fn python_random_test() {
+        // random.seed(1234)
+        let mut rng = MT19937::from_array(&[1234]);
+        let mut random = || {
+            let a = (rng.next().unwrap() >> 5) as u64;
+            let b = (rng.next().unwrap() >> 6) as u64;
+            ((a << 26) + b) as f64 / (1u64 << 53) as f64
+        };
+        let floats: Vec<f64> = (0..800).map(|_| random()).collect();
+        assert_eq!(floats[..2], [0.9664535356921388, 0.4407325991753527]);
+
+        let observations: Vec<_> = floats
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &x)| Observation::python_random(2 * i as u64, x))
+            .collect();
+        let mut cloned = recover_state(&observations).unwrap();
+        // random.getrandbits(32) after the 800 floats.
+        assert_eq!(cloned.next(), Some(3969610964));
+    }
+
+    #[test]
+    fn truncated_outputs_test() {
+        let mut rng = MT19937::from_array(&[42]);
+        let mut observations = vec![];
+        for i in 0..1400 {
+            let y = rng.next().unwrap();
+            observations.push(if i % 2 == 0 {
+                Observation::top_bits(i, y >> 8, 24)
+            } else {
+                // 3 << 16 leaks the low 16 bits.
+                Observation::modulo(i, y % (3 << 16), 3 << 16)
+            });
+        }
+        assert!(matches!(
+            recover_state(&observations[..600]),
+            Err(Error::Underdetermined(_))
+        ));
+        let mut cloned = recover_state(&observations).unwrap();
+        for _ in 0..1000 {
+            assert_eq!(rng.next(), cloned.next());
+        }
+
+        let far = [observations[0], Observation::full(MAX_SPAN, 0)];
+        assert!(matches!(
+            recover_state(&far),
+            Err(Error::ObservationSpanTooLong(span)) if span == MAX_SPAN + 1
+        ));
+        let far = [Observation::full(0, 0), Observation::full(u64::MAX, 0)];
+        assert!(matches!(
+            recover_state(&far),
+            Err(Error::ObservationSpanTooLong(u64::MAX))
+        ));
+
+        observations[10].value ^= 1 << 31;
+        assert!(matches!(
+            recover_state(&observations),
+            Err(Error::InconsistentObservations)
+        ));
+    }
+}
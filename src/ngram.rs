@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::{Error, Result},
+    freq::Scorer,
+};
+
+const MAGIC: &[u8; 3] = b"NGM";
+
+/// A byte n-gram language model, for n from 1 to 4, with log-probabilities estimated from the
+/// counts in a training corpus.
+/// Scoring looks at consecutive bytes, so unlike unigram frequencies it can tell English from
+/// gibberish even in very short texts.
+pub struct NGramModel {
+    n: usize,
+    counts: HashMap<u32, u64>,
+    log_probs: HashMap<u32, f64>,
+    /// Log-probability of an n-gram that never occurs in the corpus.
+    floor: f64,
+}
+
+/// Packs up to 4 bytes into an integer key.
+fn pack(gram: &[u8]) -> u32 {
+    gram.iter().fold(0, |acc, &c| (acc << 8) | c as u32)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        out.push(x as u8 | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+fn read_varint(data: &mut &[u8]) -> Result<u64> {
+    let mut x = 0;
+    for shift in (0..64).step_by(7) {
+        let (&c, rest) = data.split_first().ok_or(Error::InvalidModel)?;
+        *data = rest;
+        x |= ((c & 0x7f) as u64) << shift;
+        if c < 0x80 {
+            return Ok(x);
+        }
+    }
+    Err(Error::InvalidModel)
+}
+
+impl NGramModel {
+    /// Counts the n-grams of the corpus. Panics unless 1 <= n <= 4.
+    pub fn train(n: usize, corpus: &[u8]) -> Self {
+        assert!(
+            (1..=4).contains(&n),
+            "n-grams of size {} are not supported",
+            n
+        );
+        let mut counts = HashMap::new();
+        for gram in corpus.windows(n) {
+            *counts.entry(pack(gram)).or_insert(0) += 1;
+        }
+        Self::from_counts(n, counts)
+    }
+
+    fn from_counts(n: usize, counts: HashMap<u32, u64>) -> Self {
+        let total = counts.values().sum::<u64>().max(1) as f64;
+        let log_probs = counts
+            .iter()
+            .map(|(&k, &c)| (k, (c as f64 / total).ln()))
+            .collect();
+        Self {
+            n,
+            counts,
+            log_probs,
+            floor: (0.01 / total).ln(),
+        }
+    }
+
+    /// The size of the n-grams.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Average log-probability of the n-grams of the text. Higher is more likely.
+    /// Texts shorter than n get the floor log-probability.
+    pub fn log_likelihood(&self, text: &[u8]) -> f64 {
+        if text.len() < self.n {
+            return self.floor;
+        }
+        let total: f64 = text
+            .windows(self.n)
+            .map(|gram| *self.log_probs.get(&pack(gram)).unwrap_or(&self.floor))
+            .sum();
+        total / (text.len() - self.n + 1) as f64
+    }
+
+    /// Serializes the model: a header with n and the number of n-grams, then each n-gram in
+    /// increasing order followed by its count as a varint.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut keys: Vec<_> = self.counts.keys().copied().collect();
+        keys.sort_unstable();
+        let mut res = MAGIC.to_vec();
+        res.push(self.n as u8);
+        write_varint(&mut res, keys.len() as u64);
+        for k in keys {
+            res.extend_from_slice(&k.to_be_bytes()[4 - self.n..]);
+            write_varint(&mut res, self.counts[&k]);
+        }
+        res
+    }
+
+    /// Loads a model serialized by to_bytes.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let data = data.strip_prefix(MAGIC).ok_or(Error::InvalidModel)?;
+        let (&n, mut data) = data.split_first().ok_or(Error::InvalidModel)?;
+        let n = n as usize;
+        if !(1..=4).contains(&n) {
+            return Err(Error::InvalidModel);
+        }
+        let len = read_varint(&mut data)?;
+        let mut counts = HashMap::new();
+        for _ in 0..len {
+            if data.len() < n {
+                return Err(Error::InvalidModel);
+            }
+            let (gram, rest) = data.split_at(n);
+            data = rest;
+            counts.insert(pack(gram), read_varint(&mut data)?);
+        }
+        if !data.is_empty() {
+            return Err(Error::InvalidModel);
+        }
+        Ok(Self::from_counts(n, counts))
+    }
+}
+
+/// Scores with the negated log-likelihood, so that lower is better.
+impl Scorer for NGramModel {
+    fn score(&self, text: &[u8]) -> f64 {
+        -self.log_likelihood(text)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        ciphers::{
+            decrypt_caesar, decrypt_vigenere_fixed, fixed_xor, refine_repeating_xor_key,
+            repeating_xor,
+        },
+        freq::L1,
+    };
+
+    use super::*;
+
+    /// The training corpus. The plaintexts of the tests below are not in it.
+    fn corpus() -> Vec<u8> {
+        let mut corpus = include_bytes!("../data/funky_music.txt").to_vec();
+        corpus.extend_from_slice(include_bytes!("../data/challenge20out.txt"));
+        corpus
+    }
+
+    #[test]
+    fn short_text_test() {
+        let model = NGramModel::train(4, &corpus());
+        let plain = b"I'm busy";
+        let cipher = repeating_xor(plain, &[0x58]);
+        let (decrypted, _) = decrypt_caesar(&cipher, &model);
+        assert_eq!(decrypted, plain);
+        // Unigram frequencies are not enough for this one.
+        let (decrypted, _) = decrypt_caesar(&cipher, &L1::english());
+        assert_ne!(decrypted, plain);
+    }
+
+    #[test]
+    fn short_repeating_xor_test() {
+        let model = NGramModel::train(4, &corpus());
+        let plain = b"Keep the money in the safe until we return";
+        let key = b"Vanilla";
+        let cipher = repeating_xor(plain, key);
+        // Columns of 6 bytes are too short for unigram frequencies.
+        let (decrypted, _) = decrypt_vigenere_fixed(&cipher, key.len(), &L1::english());
+        assert_ne!(decrypted, plain);
+        let guess = fixed_xor(&cipher[..key.len()], &decrypted[..key.len()]);
+        let (refined, decrypted, _) = refine_repeating_xor_key(&cipher, &guess, &model);
+        assert_eq!(refined, key);
+        assert_eq!(decrypted, plain);
+    }
+
+    #[test]
+    fn serialization_test() {
+        for n in 1..=4 {
+            let model = NGramModel::train(n, &corpus());
+            let bytes = model.to_bytes();
+            let loaded = NGramModel::from_bytes(&bytes).unwrap();
+            assert_eq!(loaded.n(), n);
+            assert_eq!(loaded.counts, model.counts);
+            let text = b"Yo, I'm rated R";
+            assert_eq!(loaded.score(text), model.score(text));
+            assert!(matches!(
+                NGramModel::from_bytes(&bytes[..bytes.len() - 1]),
+                Err(Error::InvalidModel)
+            ));
+        }
+        assert!(matches!(
+            NGramModel::from_bytes(b"NGM\x05\x00"),
+            Err(Error::InvalidModel)
+        ));
+    }
+}
@@ -3,8 +3,9 @@ pub mod ra_ctr;
 pub mod timing_attack;
 
 use crate::{
-    ciphers::{decrypt_aes_128_ecb, encrypt_aes_128_cbc, encrypt_aes_128_ecb},
+    ciphers::{encrypt_cbc, encrypt_ecb, Aes128, Ecb},
     convert::from_base64,
+    error::Result,
     util::{parse_cookie, url_encode},
 };
 
@@ -21,20 +22,26 @@ pub trait Oracle {
 /// then encrypts it either with CBC or ECB mode, chosen at random.
 /// Uses a secret but consistent key.
 /// Returns the ciphertext and the mode used (true for ECB)
-pub struct EcbOrCbc {
+pub struct EcbOrCbc<R = ChaCha8Rng> {
     is_ecb: bool,
-    secret_key: [u8; 16],
-    rng: ChaCha8Rng,
+    cipher: Aes128,
+    rng: R,
 }
 
 impl EcbOrCbc {
     pub fn new(seed: u64) -> Self {
-        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Self::from_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> EcbOrCbc<R> {
+    /// Draws the key and all the random choices from rng.
+    pub fn from_rng(mut rng: R) -> Self {
         let mut secret_key = [0; 16];
         rng.fill(&mut secret_key);
         Self {
             is_ecb: false,
-            secret_key,
+            cipher: Aes128::new(&secret_key).unwrap(),
             rng,
         }
     }
@@ -43,7 +50,7 @@ impl EcbOrCbc {
     }
 }
 
-impl Oracle for EcbOrCbc {
+impl<R: Rng> Oracle for EcbOrCbc<R> {
     fn query(&mut self, plain: &[u8]) -> Vec<u8> {
         self.is_ecb = self.rng.gen();
         let mut text: Vec<u8> = vec![];
@@ -57,11 +64,11 @@ impl Oracle for EcbOrCbc {
             text.push(self.rng.gen());
         }
         if self.is_ecb {
-            encrypt_aes_128_ecb(&text, &self.secret_key)
+            encrypt_ecb(&self.cipher, &text)
         } else {
             let mut iv = [0u8; 16];
             self.rng.fill(&mut iv);
-            encrypt_aes_128_cbc(&text, &self.secret_key, &iv)
+            encrypt_cbc(&self.cipher, &text, &iv).unwrap()
         }
     }
 }
@@ -70,7 +77,7 @@ impl Oracle for EcbOrCbc {
 /// Adds a constant unknown suffix to the input, then encrypts in ECB mode.
 /// Uses a secret but consistent key.
 pub struct SecretSuffix {
-    secret_key: [u8; 16],
+    ecb: Ecb<Aes128>,
     secret_message: Vec<u8>,
     prefix: Vec<u8>,
 }
@@ -80,21 +87,21 @@ impl Oracle for SecretSuffix {
         let mut text = self.prefix.clone();
         text.extend_from_slice(plain);
         text.extend_from_slice(&self.secret_message);
-        encrypt_aes_128_ecb(&text, &self.secret_key)
+        self.ecb.encrypt(&text)
     }
 }
 
 impl SecretSuffix {
     pub fn new() -> Self {
         Self {
-            secret_key: *b"MANNY && GLOTTIS",
+            ecb: Ecb::new(Aes128::new(b"MANNY && GLOTTIS").unwrap()),
             secret_message: from_base64(
                 b"Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkg
 aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq
 dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUg
 YnkK",
             )
-            .to_vec(),
+            .unwrap(),
             prefix: vec![],
         }
     }
@@ -177,7 +184,7 @@ pub fn solve_secret_suffix(oracle: &mut SecretSuffix) -> Vec<u8> {
 }
 pub struct UserProfile {
     uid: u64,
-    secret_key: [u8; 16],
+    ecb: Ecb<Aes128>,
 }
 
 impl Oracle for UserProfile {
@@ -186,7 +193,7 @@ impl Oracle for UserProfile {
         plain.extend_from_slice(&url_encode(email));
         plain.extend_from_slice(format!("&uid={}&role=user", self.uid).as_bytes());
         self.uid += 1;
-        encrypt_aes_128_ecb(&plain, &self.secret_key)
+        self.ecb.encrypt(&plain)
     }
 }
 
@@ -194,11 +201,11 @@ impl UserProfile {
     pub fn new() -> Self {
         Self {
             uid: 10,
-            secret_key: *b"YELLOW SUBMARINE",
+            ecb: Ecb::new(Aes128::new(b"YELLOW SUBMARINE").unwrap()),
         }
     }
-    pub fn parse(&self, encrypted_token: &[u8]) -> HashMap<Vec<u8>, Vec<u8>> {
-        let plain = decrypt_aes_128_ecb(encrypted_token, &self.secret_key);
+    pub fn parse(&self, encrypted_token: &[u8]) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+        let plain = self.ecb.decrypt(encrypted_token)?;
         parse_cookie(&plain, b'&')
     }
 }
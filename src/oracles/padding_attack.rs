@@ -2,8 +2,10 @@ use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
 
 use crate::{
-    ciphers::{decrypt_aes_128_cbc, encrypt_aes_128_cbc},
-    convert::from_base64, util::unpad_in_place,
+    ciphers::{Aes128, Cbc},
+    convert::from_base64,
+    error::Result,
+    util::unpad_in_place,
 };
 
 const TEXTS_B64: &[&[u8]] = &[
@@ -21,7 +23,7 @@ const TEXTS_B64: &[&[u8]] = &[
 
 pub struct PadAttackServer {
     rng: ChaChaRng,
-    secret_key: [u8; 16],
+    cbc: Cbc<Aes128>,
     last_plaintext: Vec<u8>,
 }
 
@@ -32,7 +34,7 @@ impl PadAttackServer {
         rng.fill(&mut secret_key);
         Self {
             rng,
-            secret_key,
+            cbc: Cbc::new(Aes128::new(&secret_key).unwrap()),
             last_plaintext: vec![],
         }
     }
@@ -46,32 +48,34 @@ impl PadAttackServer {
     /// Returns the ciphertext and IV used.
     pub fn encrypt(&mut self) -> (Vec<u8>, [u8; 16]) {
         let idx = self.rng.gen::<usize>() % TEXTS_B64.len();
-        self.last_plaintext = from_base64(TEXTS_B64[idx]);
+        self.last_plaintext = from_base64(TEXTS_B64[idx]).unwrap();
         let iv = {
             let mut v = [0u8; 16];
             self.rng.fill(&mut v);
             v
         };
         (
-            encrypt_aes_128_cbc(&self.last_plaintext, &self.secret_key, &iv),
+            self.cbc.encrypt(&self.last_plaintext, &iv).unwrap(),
             iv,
         )
     }
 
     /// Attempts to decrypt the cipher with the given IV.
-    /// Returns whether the plaintext has correct padding.
+    /// Returns whether the plaintext has correct padding; any other error, such as an IV of the
+    /// wrong length, counts as bad padding.
+    /// Only the last block is decrypted, using the block before it (or the IV) in its place.
     pub fn check_padding(&self, cipher: &[u8], iv: &[u8]) -> bool {
-        // Temporarily get rid of the normal panic hook (printing out an error message/stack trace)
-        std::panic::set_hook(Box::new(|_| ()));
-        let res = std::panic::catch_unwind(|| decrypt_aes_128_cbc(cipher, &self.secret_key, iv));
-        // Restore the original panic hook
-        let _ = std::panic::take_hook();
-        res.is_ok()
+        let n = cipher.len();
+        if n < 16 || !n.is_multiple_of(16) {
+            return false;
+        }
+        let prev_block = if n == 16 { iv } else { &cipher[n - 32..n - 16] };
+        self.cbc.decrypt(&cipher[n - 16..], prev_block).is_ok()
     }
 }
 
 /// Given a ciphertext and IV, compute the plaintext by using the server's padding oracle
-pub fn attack(server: &mut PadAttackServer, cipher: &[u8], iv: &[u8]) -> Vec<u8> {
+pub fn attack(server: &PadAttackServer, cipher: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
     let block_size = iv.len();
     let n = cipher.len();
     let mut plain = vec![0u8; n];
@@ -112,7 +116,41 @@ pub fn attack(server: &mut PadAttackServer, cipher: &[u8], iv: &[u8]) -> Vec<u8>
             }
         }
     }
-    unpad_in_place(&mut plain);
-    plain
+    unpad_in_place(&mut plain)?;
+    Ok(plain)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_attack_test() {
+        let mut server = PadAttackServer::new();
+        let targets: Vec<_> = (0..4)
+            .map(|_| {
+                let (cipher, iv) = server.encrypt();
+                (cipher, iv, server.last_plaintext().to_vec())
+            })
+            .collect();
+        let server = &server;
+        std::thread::scope(|scope| {
+            for (cipher, iv, plain) in &targets {
+                scope.spawn(move || {
+                    assert_eq!(&attack(server, cipher, iv).unwrap(), plain);
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn check_padding_test() {
+        let mut server = PadAttackServer::new();
+        let (cipher, iv) = server.encrypt();
+        assert!(server.check_padding(&cipher, &iv));
+        assert!(!server.check_padding(&cipher[..cipher.len() - 1], &iv));
+        assert!(!server.check_padding(&cipher[cipher.len() - 16..], &iv[..8]));
+        assert!(!server.check_padding(&cipher[cipher.len() - 16..], &[]));
+    }
 }
 
@@ -1,25 +1,27 @@
 use std::cmp::min;
 
-use crate::ciphers::encrypt_aes_128_ctr;
+use crate::ciphers::{Aes128, CtrStream, Transform};
 
 pub struct RandomAccessCTR {
-    secret_key: [u8; 16],
-    plain: Vec<u8>,
+    ctr: CtrStream<Aes128>,
+    cipher: Vec<u8>,
 }
 
 impl RandomAccessCTR {
     pub fn new(secret_key: &[u8], plain: &[u8]) -> Self {
-        Self {
-            secret_key: secret_key.try_into().unwrap(),
-            plain: plain.to_vec(),
-        }
+        let mut ctr = CtrStream::new(Aes128::new(secret_key).unwrap(), &[b'2'; 8]).unwrap();
+        let cipher = ctr.update(plain).unwrap();
+        Self { ctr, cipher }
     }
     pub fn ciphertext(&self) -> Vec<u8> {
-        encrypt_aes_128_ctr(&self.plain, &self.secret_key, &[b'2'; 8])
+        self.cipher.clone()
     }
+    /// Replaces the plaintext at the offset, re-encrypting only the edited bytes.
     pub fn edit(&mut self, offset: usize, new_text: &[u8]) {
-        assert!(offset <= self.plain.len());
-        let m = min(new_text.len(), self.plain.len() - offset);
-        self.plain[offset..offset+m].copy_from_slice(&new_text[0..m]);
+        assert!(offset <= self.cipher.len());
+        let m = min(new_text.len(), self.cipher.len() - offset);
+        self.ctr.seek(offset as u64);
+        let edited = self.ctr.update(&new_text[0..m]).unwrap();
+        self.cipher[offset..offset + m].copy_from_slice(&edited);
     }
 }
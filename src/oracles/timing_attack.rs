@@ -1,14 +1,15 @@
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 
-pub struct Server {
-    rng: ChaCha8Rng,
+/// A server comparing MACs byte by byte, with timing noise drawn from R.
+pub struct Server<R = ChaCha8Rng> {
+    rng: R,
     base_time_micros: i64,
     var_time_micros: i64,
 }
 
-impl Server {
-    pub fn new(rng: ChaCha8Rng, base_time_micros: i64, var_time_micros: i64) -> Self { Self { rng, base_time_micros, var_time_micros } }
+impl<R: Rng> Server<R> {
+    pub fn new(rng: R, base_time_micros: i64, var_time_micros: i64) -> Self { Self { rng, base_time_micros, var_time_micros } }
 
     /// Compares two slices, character by character.
     /// Returns the (mock) total time taken for the comparison
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::error::{Error, Result};
+
 pub fn hamming_distance(a: &[u8], b: &[u8]) -> usize {
     assert_eq!(a.len(), b.len());
     a.iter()
@@ -38,18 +40,23 @@ pub fn padding_length(s: &[u8]) -> usize {
 }
 
 /// Inverse PKCS#7 padding
-pub fn unpad_in_place(s: &mut Vec<u8>) {
+pub fn unpad_in_place(s: &mut Vec<u8>) -> Result<()> {
     let n = s.len();
-    assert!(n > 0);
+    if n == 0 {
+        return Err(Error::BadPadding);
+    }
     let pad_length = padding_length(s);
-    assert!(pad_length != 0);
+    if pad_length == 0 {
+        return Err(Error::BadPadding);
+    }
     s.truncate(n - pad_length);
+    Ok(())
 }
 
-pub fn unpad(s: &[u8]) -> Vec<u8> {
+pub fn unpad(s: &[u8]) -> Result<Vec<u8>> {
     let mut res = s.to_vec();
-    unpad_in_place(&mut res);
-    res
+    unpad_in_place(&mut res)?;
+    Ok(res)
 }
 
 /// Encodes a byte slice into URL form.
@@ -71,21 +78,23 @@ pub fn url_encode(s: &[u8]) -> Vec<u8> {
 
 /// Decodes a byte slice from URL form.
 /// Only treats some characters as special.
-/// Panics on invalid %-code.
-pub fn url_decode(s: &[u8]) -> Vec<u8> {
+/// Fails on invalid %-code.
+pub fn url_decode(s: &[u8]) -> Result<Vec<u8>> {
     let n = s.len();
     let mut res = vec![];
     let mut i = 0;
     while i < n {
         if s[i] == b'%' {
-            assert!(i < n - 1, "invalid %-code");
+            if i + 2 >= n {
+                return Err(Error::InvalidUrlEncoding);
+            }
             match &s[i + 1..=i + 2] {
                 b"20" => res.push(b' '),
                 b"25" => res.push(b'%'),
                 b"26" => res.push(b'&'),
                 b"3B" => res.push(b';'),
                 b"3D" => res.push(b'='),
-                _ => panic!("invalid %-code"),
+                _ => return Err(Error::InvalidUrlEncoding),
             }
             i += 3;
         } else {
@@ -93,20 +102,22 @@ pub fn url_decode(s: &[u8]) -> Vec<u8> {
             i += 1;
         }
     }
-    res
+    Ok(res)
 }
 
 /// Parses a key-value cookie string of the form "foo=bar&baz=qux&zap=zazzle"
 /// Returns a map of keys to values.
-/// Keys and values are url-decoded. Panics on decoding error or malformed string.
-pub fn parse_cookie(s: &[u8], separator: u8) -> HashMap<Vec<u8>, Vec<u8>> {
+/// Keys and values are url-decoded. Fails on decoding error or malformed string.
+pub fn parse_cookie(s: &[u8], separator: u8) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
     let mut res = HashMap::new();
     for kv in s.split(|&c| c == separator) {
         let kv: Vec<_> = kv.split(|&c| c == b'=').collect();
-        assert_eq!(kv.len(), 2, "Each kv-pair must be of the form X=Y");
-        res.insert(url_decode(kv[0]), url_decode(kv[1]));
+        if kv.len() != 2 {
+            return Err(Error::MalformedCookie);
+        }
+        res.insert(url_decode(kv[0])?, url_decode(kv[1])?);
     }
-    res
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -125,9 +136,14 @@ pub mod tests {
             b"YELLOW SUBMARINE\x04\x04\x04\x04"
         );
         assert_eq!(
-            unpad(b"YELLOW SUBMARINE\x04\x04\x04\x04"),
+            unpad(b"YELLOW SUBMARINE\x04\x04\x04\x04").unwrap(),
             b"YELLOW SUBMARINE"
         );
+        assert!(matches!(
+            unpad(b"YELLOW SUBMARINE\x04\x04\x03\x04"),
+            Err(Error::BadPadding)
+        ));
+        assert!(matches!(unpad(b""), Err(Error::BadPadding)));
     }
 
     #[test]
@@ -135,7 +151,9 @@ pub mod tests {
         let raw = b"foo@bar.com&role=admin%";
         let encoded = b"foo@bar.com%26role%3Dadmin%25";
         assert_eq!(&url_encode(raw), encoded);
-        assert_eq!(&url_decode(encoded), raw);
+        assert_eq!(&url_decode(encoded).unwrap(), raw);
+        assert!(matches!(url_decode(b"foo%2"), Err(Error::InvalidUrlEncoding)));
+        assert!(matches!(url_decode(b"foo%41"), Err(Error::InvalidUrlEncoding)));
     }
     #[test]
     fn parse_cookie_test() {
@@ -146,6 +164,10 @@ pub mod tests {
             (b"zap".to_vec(), b"zazzle".to_vec()),
             (b"%&".to_vec(), b"a=b".to_vec()),
         ]);
-        assert_eq!(parse_cookie(cookie, b'&'), map);
+        assert_eq!(parse_cookie(cookie, b'&').unwrap(), map);
+        assert!(matches!(
+            parse_cookie(b"foo=bar&baz", b'&'),
+            Err(Error::MalformedCookie)
+        ));
     }
 }
@@ -1,6 +1,10 @@
-use crate::{
+use rand::{Rng, SeedableRng};
+
+use cryptopals::{
     ciphers::{decrypt_aes_128_cbc, encrypt_aes_128_cbc},
     convert::from_base64,
+    error::Error,
+    mersenne::MT19937,
     oracles::{solve_secret_suffix, EcbOrCbc, Oracle, SecretSuffix, UserProfile},
     util::{pad, parse_cookie, unpad},
 };
@@ -13,17 +17,16 @@ pub fn challenge9() {
 }
 
 pub fn challenge10() {
-    let cipher = from_base64(include_str!("../data/challenge10.txt").as_bytes());
+    let cipher = from_base64(include_str!("../data/challenge10.txt").as_bytes()).unwrap();
     let expected = include_str!("../data/funky_music.txt").as_bytes();
     let key = b"YELLOW SUBMARINE";
-    let plain = decrypt_aes_128_cbc(&cipher, key, &[0u8; 16]);
+    let plain = decrypt_aes_128_cbc(&cipher, key, &[0u8; 16]).unwrap();
     assert_eq!(plain, expected);
-    let re_encrypted = encrypt_aes_128_cbc(&plain, key, &[0u8; 16]);
+    let re_encrypted = encrypt_aes_128_cbc(&plain, key, &[0u8; 16]).unwrap();
     assert_eq!(re_encrypted, cipher);
 }
 
-pub fn challenge11() {
-    let mut oracle = EcbOrCbc::new(12345);
+fn detect_ecb<R: Rng>(mut oracle: EcbOrCbc<R>) {
     for _ in 0..10 {
         let plain = vec![b'x'; 64];
         let cipher = oracle.query(&plain);
@@ -32,6 +35,11 @@ pub fn challenge11() {
     }
 }
 
+pub fn challenge11() {
+    detect_ecb(EcbOrCbc::new(12345));
+    detect_ecb(EcbOrCbc::from_rng(MT19937::seed_from_u64(12345)));
+}
+
 pub fn challenge12() {
     let mut oracle = SecretSuffix::new();
     // Confirm it's ECB
@@ -45,7 +53,8 @@ pub fn challenge12() {
 aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq
 dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUg
 YnkK",
-    );
+    )
+    .unwrap();
     assert_eq!(expected, message);
 }
 
@@ -58,7 +67,7 @@ pub fn challenge13() {
     cipher.extend(&oracle.query(b"xyz@gmail.admin")[16..32]);
     // =user
     cipher.extend(&oracle.query(b"xyzz@gmail.admin")[32..48]);
-    let dict = oracle.parse(&cipher);
+    let dict = oracle.parse(&cipher).unwrap();
     assert_eq!(dict[&b"email".to_vec()], b"xyz@gmail.com");
     assert_eq!(dict[&b"role".to_vec()], b"admin");
     assert_eq!(dict[&b"rolle".to_vec()], b"user");
@@ -72,14 +81,24 @@ pub fn challenge14() {
 aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq
 dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUg
 YnkK",
-    );
+    )
+    .unwrap();
     assert_eq!(expected, message);
 }
 
 pub fn challenge15() {
-    assert_eq!(unpad(b"ICE ICE BABY\x04\x04\x04\x04"), b"ICE ICE BABY");
-    let res = std::panic::catch_unwind(|| unpad(b"ICE ICE BABY\x05\x05\x05\x05"));
-    assert!(res.is_err());
+    assert_eq!(
+        unpad(b"ICE ICE BABY\x04\x04\x04\x04").unwrap(),
+        b"ICE ICE BABY"
+    );
+    assert!(matches!(
+        unpad(b"ICE ICE BABY\x05\x05\x05\x05"),
+        Err(Error::BadPadding)
+    ));
+    assert!(matches!(
+        unpad(b"ICE ICE BABY\x01\x02\x03\x04"),
+        Err(Error::BadPadding)
+    ));
 }
 
 pub fn challenge16() {
@@ -93,13 +112,13 @@ pub fn challenge16() {
         v.extend_from_slice(suf);
         v
     };
-    let mut cipher = encrypt_aes_128_cbc(&plain, secret_key, &[0; 16]);
+    let mut cipher = encrypt_aes_128_cbc(&plain, secret_key, &[0; 16]).unwrap();
     for i in 0..16 {
         cipher[32 + i] ^= wanted[i] ^ 1;
     }
-    let hacked_plain = decrypt_aes_128_cbc(&cipher, secret_key, &[0; 16]);
+    let hacked_plain = decrypt_aes_128_cbc(&cipher, secret_key, &[0; 16]).unwrap();
     assert!(hacked_plain.windows(16).any(|w| w == wanted));
-    let dict = parse_cookie(&hacked_plain, b';');
+    let dict = parse_cookie(&hacked_plain, b';').unwrap();
     assert!(dict[&b"role".to_vec()] == b"admin");
 }
 
@@ -0,0 +1,184 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cryptopals::{
+    ciphers::{
+        break_fixed_nonce_ctr_by, encrypt_aes_128_ctr, fixed_xor, mt19937_stream_cipher,
+        recover_fixed_nonce_keystream_by,
+    },
+    convert::from_base64,
+    freq::{LogLikelihood, Scorer},
+    mersenne::{
+        search::{password_reset_token, token_timestamp, SeedSearch},
+        untemper, MT19937,
+    },
+    oracles::padding_attack::{attack, PadAttackServer},
+};
+
+pub fn challenge17() {
+    let mut server = PadAttackServer::new();
+    for _ in 0..5 {
+        let (cipher, iv) = server.encrypt();
+        assert!(server.check_padding(&cipher, &iv));
+
+        let plain = attack(&server, &cipher, &iv).unwrap();
+        assert_eq!(&plain, &server.last_plaintext());
+    }
+}
+
+pub fn challenge18() {
+    let cipher =
+        from_base64(b"L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==").unwrap();
+    let plain = encrypt_aes_128_ctr(&cipher, b"YELLOW SUBMARINE", &[0u8; 8]).unwrap();
+    assert_eq!(
+        &plain,
+        b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+    );
+    let re_encrypted = encrypt_aes_128_ctr(&plain, b"YELLOW SUBMARINE", &[0u8; 8]).unwrap();
+    assert_eq!(&re_encrypted, &cipher);
+}
+
+/// Encrypts each line of the file under the same CTR key and nonce.
+fn fixed_nonce_ciphers(data: &str) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let key: [u8; 16] = rand::random();
+    let plains: Vec<Vec<u8>> = data
+        .lines()
+        .map(|line| from_base64(line.as_bytes()).unwrap())
+        .collect();
+    let ciphers = plains
+        .iter()
+        .map(|p| encrypt_aes_128_ctr(p, &key, &[0; 8]).unwrap())
+        .collect();
+    (plains, ciphers)
+}
+
+/// The fraction of bytes recovered correctly.
+fn accuracy(plains: &[Vec<u8>], recovered: &[(Vec<u8>, Vec<f64>)]) -> f64 {
+    let total: usize = plains.iter().map(|p| p.len()).sum();
+    let correct: usize = plains
+        .iter()
+        .zip(recovered)
+        .map(|(p, (d, _))| p.iter().zip(d).filter(|(x, y)| x == y).count())
+        .sum();
+    correct as f64 / total as f64
+}
+
+/// Scores the columns of lines that start like sentences. Unigram statistics take the capital
+/// first letters for lower case, so the first column is scored with its case swapped.
+fn sentence_score(english: &LogLikelihood) -> impl Fn(usize, &[u8]) -> f64 + '_ {
+    move |i, column| {
+        if i == 0 {
+            let swapped: Vec<u8> = column
+                .iter()
+                .map(|&c| if c.is_ascii_alphabetic() { c ^ 0x20 } else { c })
+                .collect();
+            english.score(&swapped)
+        } else {
+            english.score(column)
+        }
+    }
+}
+
+pub fn challenge19() {
+    let (plains, ciphers) = fixed_nonce_ciphers(include_str!("../data/challenge19.txt"));
+    let english = LogLikelihood::english();
+    let recovered = break_fixed_nonce_ctr_by(&ciphers, sentence_score(&english));
+    assert_eq!(recovered[0].0, b"I have met them at close of day");
+    // Only the tails of the longest lines, with few samples per column, may be wrong.
+    let min_len = plains.iter().map(|s| s.len()).min().unwrap();
+    for (plain, (decrypted, _)) in plains.iter().zip(&recovered) {
+        assert_eq!(decrypted[..min_len], plain[..min_len]);
+    }
+    assert!(accuracy(&plains, &recovered) > 0.95);
+}
+
+pub fn challenge20() {
+    let (plains, ciphers) = fixed_nonce_ciphers(include_str!("../data/challenge20.txt"));
+    let english = LogLikelihood::english();
+    let keystream = recover_fixed_nonce_keystream_by(&ciphers, sentence_score(&english));
+    let keystream: Vec<u8> = keystream.into_iter().map(|(k, _)| k).collect();
+    let min_len = plains.iter().map(|s| s.len()).min().unwrap();
+    let decrypted: Vec<u8> = ciphers
+        .iter()
+        .flat_map(|c| fixed_xor(&c[..min_len], &keystream[..min_len]))
+        .collect();
+    let res: Vec<u8> = include_str!("../data/challenge20out.txt")
+        .bytes()
+        .filter(|&c| c != b'\n')
+        .collect();
+    assert_eq!(&decrypted, &res);
+    let recovered = break_fixed_nonce_ctr_by(&ciphers, sentence_score(&english));
+    assert!(accuracy(&plains, &recovered) > 0.95);
+}
+
+pub fn challenge21() {
+    let rng = MT19937::new(1234);
+    let nums: Vec<_> = rng.take(10).collect();
+    assert_eq!(
+        &nums,
+        &[
+            822569775, 2137449171, 2671936806, 3512589365, 1880026316, 2629000564, 3373089432,
+            3312965625, 3349970575, 3696548529
+        ]
+    );
+}
+
+pub fn challenge22() {
+    let mut rng = MT19937::new(1234);
+    let offset = (rng.next().unwrap() % 960 + 40) as u64;
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        - offset;
+    let mut rng = MT19937::new(seed as u32);
+    let n = rng.next().unwrap();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let hacked_seed = SeedSearch::time_window(now, 2000).find_outputs(0, &[n]);
+    assert_eq!(hacked_seed, Some(seed as u32));
+}
+
+pub fn challenge23() {
+    let mut rng = MT19937::new(1234);
+    let mut state = [0; 624];
+    for x in &mut state {
+        *x = untemper(rng.next().unwrap());
+    }
+    let mut cloned_rng = MT19937::from_state(&state);
+    for _ in 0..10 {
+        assert_eq!(rng.next(), cloned_rng.next());
+    }
+    assert_eq!(cloned_rng.recover_seed(1000), Some((1234, 634)));
+}
+
+pub fn challenge24() {
+    let mut plain = b"Blargh!".to_vec();
+    plain.extend_from_slice(&[b'A'; 14]);
+    let cipher = mt19937_stream_cipher(&plain, 1234);
+    let n = cipher.len();
+    let key = SeedSearch::u16().find_stream_key(&cipher, n - 14, &[b'A'; 14]);
+    assert_eq!(key, Some(1234));
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let token = password_reset_token(now as u32 - 100, 16);
+    assert_eq!(token_timestamp(&token, now, 3600), Some(now as u32 - 100));
+    let random_token: [u8; 16] = rand::random();
+    assert_eq!(token_timestamp(&random_token, now, 3600), None);
+}
+
+#[test]
+fn test_challenges() {
+    challenge17();
+    challenge18();
+    challenge19();
+    challenge20();
+    challenge21();
+    challenge22();
+    challenge23();
+    challenge24();
+}
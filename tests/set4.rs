@@ -1,9 +1,12 @@
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-use crate::{
+use cryptopals::{
     ciphers::{decrypt_aes_128_cbc, encrypt_aes_128_cbc, encrypt_aes_128_ctr, fixed_xor},
-    mac::{extend_sha1, generate_sha1_mac, pad_with_length, verify_sha1_mac, generate_md4_mac, extend_md4, generate_sha1_hmac},
+    mac::{
+        forge_length_extension, generate_md4_mac, generate_sha1_hmac, generate_sha1_mac,
+        verify_md4_mac, verify_sha1_mac, Md4, Sha1,
+    },
     oracles::{ra_ctr::RandomAccessCTR, timing_attack},
     util::parse_cookie,
 };
@@ -29,25 +32,25 @@ pub fn challenge26() {
         v.extend_from_slice(suf);
         v
     };
-    let mut cipher = encrypt_aes_128_ctr(&plain, secret_key, &[0; 8]);
+    let mut cipher = encrypt_aes_128_ctr(&plain, secret_key, &[0; 8]).unwrap();
     let wanted = b";role=admin;a=";
     for i in 0..wanted.len() {
         cipher[pref.len() + i] ^= wanted[i];
     }
-    let hacked_plain = encrypt_aes_128_ctr(&cipher, secret_key, &[0; 8]);
-    let dict = parse_cookie(&hacked_plain, b';');
+    let hacked_plain = encrypt_aes_128_ctr(&cipher, secret_key, &[0; 8]).unwrap();
+    let dict = parse_cookie(&hacked_plain, b';').unwrap();
     assert_eq!(dict[&b"role".to_vec()], b"admin");
 }
 
 pub fn challenge27() {
     let secret_key = b"MANNY && GLOTTIS";
     let plain = [b'a'; 16 * 5];
-    let cipher = encrypt_aes_128_cbc(&plain, secret_key, secret_key);
+    let cipher = encrypt_aes_128_cbc(&plain, secret_key, secret_key).unwrap();
     let mut attack = cipher[..16].to_vec();
     attack.extend_from_slice(&[0; 16]);
     attack.extend_from_within(0..16);
     attack.extend_from_slice(&cipher[48..]);
-    let res = decrypt_aes_128_cbc(&attack, secret_key, secret_key);
+    let res = decrypt_aes_128_cbc(&attack, secret_key, secret_key).unwrap();
     assert!(res.iter().any(|&c| c >= 128));
     let cracked_key = fixed_xor(&res[..16], &res[32..48]);
     assert_eq!(&cracked_key, secret_key);
@@ -64,36 +67,30 @@ pub fn challenge29() {
     let secret_key = b"potato";
     let orig = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
     let orig_mac = generate_sha1_mac(orig, secret_key);
-    let guessed_key_len = secret_key.len();
     let suffix = b";admin=true";
-    let forged_message = {
-        let mut msg = vec![0; guessed_key_len];
-        msg.extend_from_slice(orig);
-        let mut s = pad_with_length(&msg, msg.len() * 8, true);
-        s.extend_from_slice(suffix);
-        s[guessed_key_len..].to_vec()
-    };
-    let forged_mac = extend_sha1(&orig_mac, suffix, guessed_key_len + forged_message.len());
+    let forgery = forge_length_extension::<Sha1>(orig, &orig_mac, suffix, 64, |msg, mac| {
+        verify_sha1_mac(msg, secret_key, mac)
+    })
+    .unwrap();
 
-    assert_eq!(forged_mac, generate_sha1_mac(&forged_message, secret_key));
+    assert_eq!(forgery.key_len, secret_key.len());
+    assert!(forgery.message.ends_with(suffix));
+    assert_eq!(forgery.mac, generate_sha1_mac(&forgery.message, secret_key));
 }
 
 pub fn challenge30() {
     let secret_key = b"potato";
     let orig = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
     let orig_mac = generate_md4_mac(orig, secret_key);
-    let guessed_key_len = secret_key.len();
     let suffix = b";admin=true";
-    let forged_message = {
-        let mut msg = vec![0; guessed_key_len];
-        msg.extend_from_slice(orig);
-        let mut s = pad_with_length(&msg, msg.len() * 8, false);
-        s.extend_from_slice(suffix);
-        s[guessed_key_len..].to_vec()
-    };
-    let forged_mac = extend_md4(&orig_mac, suffix, guessed_key_len + forged_message.len());
+    let forgery = forge_length_extension::<Md4>(orig, &orig_mac, suffix, 64, |msg, mac| {
+        verify_md4_mac(msg, secret_key, mac)
+    })
+    .unwrap();
 
-    assert_eq!(forged_mac, generate_md4_mac(&forged_message, secret_key));
+    assert_eq!(forgery.key_len, secret_key.len());
+    assert!(forgery.message.ends_with(suffix));
+    assert_eq!(forgery.mac, generate_md4_mac(&forgery.message, secret_key));
 }
 
 pub fn challenge31() {
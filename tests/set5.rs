@@ -1,6 +1,6 @@
 use num_bigint::BigUint;
 
-use crate::num::to_hash;
+use cryptopals::{kdf::KeyDerivation, num::to_hash};
 
 pub fn challenge33() {
     let p = BigUint::parse_bytes(
@@ -16,6 +16,15 @@ pub fn challenge33() {
     let a_key = to_hash(&a_exp.modpow(&b, &p));
     let b_key = to_hash(&b_exp.modpow(&a, &p));
     assert_eq!(&a_key, &b_key);
+
+    let kdf = KeyDerivation::Hkdf {
+        salt: b"challenge 33".to_vec(),
+        info: b"AES-128 key".to_vec(),
+    };
+    let a_aes_key = kdf.derive(&a_exp.modpow(&b, &p), 16).unwrap();
+    let b_aes_key = kdf.derive(&b_exp.modpow(&a, &p), 16).unwrap();
+    assert_eq!(a_aes_key, b_aes_key);
+    assert_ne!(a_aes_key, a_key[..16]);
 }
 
 #[test]